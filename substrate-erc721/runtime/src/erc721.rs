@@ -1,25 +1,133 @@
 // Port of the OpenZeppelin ERC721 and ERC721Enumerable contracts to Parity Substrate
 // https://github.com/OpenZeppelin/openzeppelin-solidity/tree/master/contracts/token/ERC721
 
-use parity_codec::Encode;
+use parity_codec::{Encode, Decode};
 use srml_support::{StorageValue, StorageMap, dispatch::Result};
-use system::ensure_signed;
-use runtime_primitives::traits::{Hash, Zero};
+use system::{ensure_signed, ensure_root};
+use runtime_primitives::traits::{Hash, Zero, Verify, As, CheckedAdd, CheckedMul};
 use rstd::prelude::*;
 
+#[cfg(test)]
+#[path = "mock.rs"]
+mod mock;
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
+
+// Seed for the account owned by this module, see `account_id`
+pub const MODULE_ID: [u8; 8] = *b"erc721md";
+
+// Upper bound on the number of entries a paged read returns
+const MAX_PAGE_SIZE: u64 = 100;
+
+// Upper bound on the number of tokens a single scan of `AllTokens` visits
+const MAX_SCAN_LENGTH: u64 = 1000;
+
+// Upper bound on the number of recipients a token can be restricted to
+const MAX_RECIPIENTS_PER_TOKEN: usize = 50;
+
+// Upper bound on the number of open offers on one token
+const MAX_OFFERS_PER_TOKEN: usize = 50;
+
+// Upper bound on the number of locks held on one token, and on the length of a lock purpose
+const MAX_LOCKS_PER_TOKEN: usize = 8;
+const MAX_LOCK_PURPOSE_LENGTH: usize = 32;
+
+// Upper bound on the length of a transfer memo, in bytes
+const MAX_MEMO_LENGTH: usize = 256;
+
+// Upper bound on the length of the external asset reference of a wrapped token, in bytes
+const MAX_ASSET_REF_LENGTH: usize = 128;
+
+// Domain tags signed at the start of every off-chain payload, so a signature made for one call
+// can never be replayed through another
+pub const ORDER_DOMAIN: &[u8] = b"erc721/order";
+pub const EXCHANGE_DOMAIN: &[u8] = b"erc721/exchange";
+pub const CONSENT_DOMAIN: &[u8] = b"erc721/consent";
+
 pub trait Trait: balances::Trait {
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    type TransferPolicy: TransferPolicy<Self::AccountId, Self::Hash>;
+    type MarketplaceHook: MarketplaceHook<Self::AccountId, Self::Hash, Self::Balance>;
+    // Derives ids for newly created tokens, separate from `system::Trait::Hashing` so ids can
+    // match an external scheme (e.g. keccak256). Set it to `Hashing` to keep the chain's hash
+    type TokenIdHasher: Hash<Output = Self::Hash>;
+    // Signatures accounts make off-chain over orders and other payloads
+    type Signature: Verify<Signer = Self::AccountId> + Decode;
+}
+
+// Lets a runtime reject transfers with its own rules (compliance, game logic, ...)
+// without forking this module. `()` allows every transfer
+pub trait TransferPolicy<AccountId, Hash> {
+    fn can_transfer(from: &AccountId, to: &AccountId, token_id: &Hash) -> Result;
+}
+
+impl<AccountId, Hash> TransferPolicy<AccountId, Hash> for () {
+    fn can_transfer(_: &AccountId, _: &AccountId, _: &Hash) -> Result {
+        Ok(())
+    }
+}
+
+// Lets a runtime hand freshly minted tokens to a marketplace module, see `mint_and_list`
+// `()` accepts every listing without doing anything
+pub trait MarketplaceHook<AccountId, Hash, Balance> {
+    fn list(seller: &AccountId, token_id: &Hash, price: Balance) -> Result;
+}
+
+impl<AccountId, Hash, Balance> MarketplaceHook<AccountId, Hash, Balance> for () {
+    fn list(_: &AccountId, _: &Hash, _: Balance) -> Result {
+        Ok(())
+    }
+}
+
+// Why a `try_transfer` did or did not happen. It never aborts, so batches can skip failures
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TransferOutcome {
+    Success,
+    NoToken,
+    NotOwner,
+    NotApproved,
+    // Refused by a transfer rule, e.g. the `TransferPolicy` or a recipient whitelist
+    Rejected,
 }
 
 decl_event!(
     pub enum Event<T>
     where
         <T as system::Trait>::AccountId,
-        <T as system::Trait>::Hash
+        <T as system::Trait>::Hash,
+        <T as balances::Trait>::Balance
     {
         Transfer(Option<AccountId>, Option<AccountId>, Hash),
         Approval(AccountId, AccountId, Hash),
         ApprovalForAll(AccountId, AccountId, bool),
+        TransferWithMemo(AccountId, AccountId, Hash, Vec<u8>),
+        ApprovalCleared(Hash),
+        // Owner, spender, token. The spender may make one `transfer_from` of the token
+        SingleUseApproved(AccountId, AccountId, Hash),
+        // Emitted next to the ERC721 `Transfer` so indexers need not decode the `None` convention
+        Minted(AccountId, Hash),
+        Burned(AccountId, Hash),
+        // Account, old and new `balance_of`. Only emitted while `EmitBalanceEvents` is set
+        BalanceChanged(AccountId, u64, u64),
+        Reclaimed(Hash, AccountId),
+        BundleCreated(AccountId, u32),
+        BundleTransferred(AccountId, AccountId, u32),
+        BundleDissolved(AccountId, u32),
+        SnapshotTaken(u32),
+        // Old id, new id
+        TokenRemapped(Hash, Hash),
+        // Number of transfers in a best effort batch that succeeded and that failed
+        BatchPartial(u32, u32),
+        Listed(Hash, Balance),
+        // Token, old price, new price
+        PriceChanged(Hash, Balance, Balance),
+        Delisted(Hash),
+        // Token and the reference of the external asset it represents
+        Wrapped(Hash, Vec<u8>),
+        Unwrapped(Hash),
     }
 );
 
@@ -29,9 +137,36 @@ decl_storage! {
         OwnedTokensCount get(balance_of): map T::AccountId => u64;
         TokenOwner get(owner_of): map T::Hash => Option<T::AccountId>;
         TokenApprovals get(get_approved): map T::Hash => Option<T::AccountId>;
+        // Not a part of the ERC721 specification, an approval spent by the first `transfer_from` using it
+        SingleUseApproval get(single_use_approval): map T::Hash => Option<T::AccountId>;
         OperatorApprovals get(is_approved_for_all): map (T::AccountId, T::AccountId) => bool;
         // End ERC721 : Storage & Getters //
 
+        // Not a part of the ERC721 specification, bounds the operators each owner can approve
+        MaxOperatorsPerOwner get(max_operators_per_owner) config(): u32 = 16;
+        // Upper bound on the number of entries a single batch call may touch, checked before any work
+        MaxBatchSize get(max_batch_size) config(): u32 = 50;
+        // Operators each owner currently approves, so they can all be revoked at once
+        ApprovedOperators get(approved_operators): map T::AccountId => Vec<T::AccountId>;
+        // Setting this to false keeps token approvals across transfers, which deviates from ERC721
+        ClearApprovalOnTransfer get(clear_approval_on_transfer) config(): bool = true;
+        // Keeps each owner's `OwnedTokens` in insertion order when a token leaves it, by shifting
+        // the later tokens down instead of swap-and-pop. That costs a read and two writes per later
+        // token, so a transfer out of a large portfolio gets expensive
+        StableOrdering get(stable_ordering) config(): bool;
+        // Lets an account record itself as its own operator. This grants nothing beyond owning,
+        // but keeps the operator list uniform for flows that act through a single key
+        AllowSelfOperator get(allow_self_operator) config(): bool;
+        // Makes a transfer to the token's current owner an error instead of a no-op
+        RejectSelfTransfer get(reject_self_transfer) config(): bool;
+        // Requires every token to have an approval set, e.g. to a compliance account, before it
+        // can move, even when its owner sends it
+        StrictApproval get(strict_approval) config(): bool;
+        // Emits `BalanceChanged` on every change to `OwnedTokensCount`, off by default as it doubles the events
+        EmitBalanceEvents get(emit_balance_events) config(): bool;
+        // Share of each `buy` paid to the module account, in basis points (1/10000) of the price
+        MarketplaceFeeBps get(marketplace_fee_bps) config(): u32;
+
         // Start ERC721 : Enumerable : Storage & Getters //
         TotalSupply get(total_supply): u64;
         AllTokens get(token_by_index): map u64 => T::Hash;
@@ -42,72 +177,1097 @@ decl_storage! {
 
         // Not a part of the ERC721 specification, but used in random token generation
         Nonce: u64;
+
+        // Not a part of the ERC721 specification, every owner a token has had, oldest first
+        // Kept after a burn as a permanent provenance record. There is no bound on its length,
+        // so a token that is traded very often makes this entry grow without limit
+        OwnershipHistory get(history_of): map T::Hash => Vec<T::AccountId>;
+
+        // Not a part of the ERC721 specification, operators approved for every owner by governance
+        DefaultOperators get(is_default_operator): map T::AccountId => bool;
+        // (owner, operator) pairs where the owner opted out of a default operator
+        DefaultOperatorRevoked get(is_default_operator_revoked): map (T::AccountId, T::AccountId) => bool;
+
+        // Not a part of the ERC721 specification, limits how many tokens each account may create
+        PerCreatorCap get(per_creator_cap): Option<u32>;
+        CreatorMintCount get(creator_mint_count): map T::AccountId => u32;
+        TokenCreator get(creator_of): map T::Hash => Option<T::AccountId>;
+        // When true, burning a token gives its creator back one unit of their cap
+        BurnRefundsCap get(burn_refunds_cap): bool;
+
+        // Not a part of the ERC721 specification, price of a mint, paid into the module account
+        MintPrice get(mint_price): Option<T::Balance>;
+        // Not a part of the ERC721 specification, first and last block (inclusive) minting is open
+        // Minting is always open while this is not set
+        MintWindow get(mint_window): Option<(T::BlockNumber, T::BlockNumber)>;
+
+        // Paid from the module account's balance to whoever burns a token with `burn_for_refund`
+        // Zero disables refunds
+        RefundAmount get(refund_amount) config(): T::Balance;
+
+        // Not a part of the ERC721 specification, ids only the admin may mint through `mint`
+        ReservedTokens get(is_reserved): map T::Hash => bool;
+
+        // Not a part of the ERC721 specification, accounts allowed to mint tokens to others
+        Minters get(is_minter): map T::AccountId => bool;
+
+        // Not a part of the ERC721 specification, the external asset (e.g. on a bridged chain)
+        // a token minted through `mint_wrapped` stands for. Removed when the token is burned
+        WrappedAsset get(wrapped_asset): map T::Hash => Option<Vec<u8>>;
+
+        // Not a part of the ERC721 specification, purposes (e.g. b"staking") a token is locked for
+        // Lockers are accounts root designated to add and remove locks. A locked token cannot move
+        TokenLocks get(token_locks): map T::Hash => Vec<Vec<u8>>;
+        Lockers get(is_locker): map T::AccountId => bool;
+
+        // Not a part of the ERC721 specification, recipients a token may only be sent to
+        // A token without any entries may be sent to anyone
+        TokenRecipientWhitelist get(is_whitelisted_recipient): map (T::Hash, T::AccountId) => bool;
+        // The same recipients as a list, so they can be dropped with the token. Bounded by `MAX_RECIPIENTS_PER_TOKEN`
+        WhitelistedRecipients get(whitelisted_recipients): map T::Hash => Vec<T::AccountId>;
+
+        // Not a part of the ERC721 specification, per account replay protection for relayed calls
+        AccountNonce get(nonce_of): map T::AccountId => u64;
+
+        // Not a part of the ERC721 specification, tokens minted in each block, for analytics
+        // Only bounded by how many mints fit in one block, and never pruned
+        MintedInBlock get(tokens_minted_in_block): map T::BlockNumber => Vec<T::Hash>;
+        // The block each token was minted in, so its `MintedInBlock` entry can be found again
+        MintBlock get(mint_block_of): map T::Hash => Option<T::BlockNumber>;
+        // (mints, burns, transfers) in each block, written from the counters below in `on_finalise`
+        BlockStats get(block_stats): map T::BlockNumber => (u32, u32, u32);
+        // Counters for the current block only, cleared again in `on_finalise`
+        BlockMints: u32;
+        BlockBurns: u32;
+        BlockTransfers: u32;
+
+        // Not a part of the ERC721 specification, hashes of signed orders that were already executed
+        UsedOrders get(is_order_used): map T::Hash => bool;
+
+        // Not a part of the ERC721 specification, tokens each account sent and received, oldest first
+        // Mints and burns are not included. Unbounded, so very active accounts grow these without limit
+        SentTransfers get(sent_transfers): map T::AccountId => Vec<T::Hash>;
+        ReceivedTransfers get(received_transfers): map T::AccountId => Vec<T::Hash>;
+
+        // Not a part of the ERC721 specification, sets of tokens that can only move together
+        NextBundleId get(next_bundle_id): u32;
+        Bundles get(bundle_tokens): map u32 => Vec<T::Hash>;
+        TokenBundle get(bundle_of): map T::Hash => Option<u32>;
+
+        // Not a part of the ERC721 specification, accounts holding at least one token
+        // Enumerated like `AllTokens` so snapshots can page through them
+        Holders get(is_holder): map T::AccountId => bool;
+        HolderCount get(holder_count): u64;
+        AllHolders get(holder_by_index): map u64 => T::AccountId;
+        AllHoldersIndex: map T::AccountId => u64;
+        // (tag, account) => tokens held when root took snapshot `tag`, for voting modules
+        HolderSnapshot get(snapshot_balance): map (u32, T::AccountId) => u32;
+        SnapshotTaken get(is_snapshot_taken): map u32 => bool;
+
+        // Not a part of the ERC721 specification, who put a token into escrow with `approve_escrow`
+        // Kept while the token is on its way into or held by the module account
+        EscrowDepositor get(escrow_depositor): map T::Hash => Option<T::AccountId>;
+
+        // Not a part of the ERC721 specification, fixed prices for tokens on sale through `buy`
+        // A listing is dropped whenever its token moves
+        SalePrice get(sale_price): map T::Hash => Option<T::Balance>;
+
+        // Not a part of the ERC721 specification, (token, buyer) => amount reserved for the offer
+        Offers get(offer_of): map (T::Hash, T::AccountId) => T::Balance;
+        // Accounts with an open offer on each token, so the highest one can be found again
+        // after it is withdrawn or accepted. Bounded by `MAX_OFFERS_PER_TOKEN`
+        OfferBidders get(offer_bidders): map T::Hash => Vec<T::AccountId>;
+        HighestOffer get(highest_offer): map T::Hash => Option<(T::AccountId, T::Balance)>;
     }
 }
 
-decl_module! {
-    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+
+        fn deposit_event<T>() = default;
+
+        fn on_finalise(n: T::BlockNumber) {
+            let stats = (<BlockMints<T>>::take(), <BlockBurns<T>>::take(), <BlockTransfers<T>>::take());
+            if stats != (0, 0, 0) {
+                <BlockStats<T>>::insert(n, stats);
+            }
+        }
+
+        // Start ERC721 : Public Functions //
+        fn approve(origin, to: T::AccountId, token_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+
+            ensure!(to != owner, "Owner is implicitly approved");
+            ensure!(sender == owner || Self::is_approved_for_all((owner.clone(), sender.clone())), "You are not allowed to approve for this token");
+
+            <TokenApprovals<T>>::insert(&token_id, &to);
+
+            Self::deposit_event(RawEvent::Approval(owner, to, token_id));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, `approve` for exactly one transfer, kept apart from `TokenApprovals`
+        fn approve_single_use(origin, to: T::AccountId, token_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+
+            ensure!(to != owner, "Owner is implicitly approved");
+            ensure!(sender == owner || Self::is_approved_for_all((owner.clone(), sender.clone())), "You are not allowed to approve for this token");
+
+            <SingleUseApproval<T>>::insert(&token_id, &to);
+
+            Self::deposit_event(RawEvent::SingleUseApproved(owner, to, token_id));
+
+            Ok(())
+        }
+
+        fn set_approval_for_all(origin, to: T::AccountId, approved: bool) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(to != sender || Self::allow_self_operator(), "You are already implicity approved for your own actions");
+
+            // Only a change in approval touches the operator list, so repeated calls are harmless
+            let was_approved = Self::is_approved_for_all((sender.clone(), to.clone()));
+            if approved && !was_approved {
+                let operator_count = Self::approved_operators(&sender).len();
+                ensure!(operator_count < Self::max_operators_per_owner() as usize, "Owner has approved the maximum number of operators");
+                <ApprovedOperators<T>>::mutate(&sender, |operators| operators.push(to.clone()));
+            } else if !approved && was_approved {
+                <ApprovedOperators<T>>::mutate(&sender, |operators| operators.retain(|o| *o != to));
+            }
+
+            <OperatorApprovals<T>>::insert((sender.clone(), to.clone()), approved);
+
+            Self::deposit_event(RawEvent::ApprovalForAll(sender, to, approved));
+
+            Ok(())
+        }
+
+        // transfer_from will transfer to addresses even without a balance
+        fn transfer_from(origin, from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+
+            // Check `from` first so a wrong `from` is not reported as missing approval
+            ensure!(owner == from, "'from' account does not own this token");
+            let single_use = Self::_check_spender(&owner, &sender, token_id)?;
+            Self::_check_transfer(&owner, &from, &to, token_id)?;
+
+            Self::_move_token(from, to, token_id)?;
+            Self::_spend_single_use(token_id, single_use);
+
+            Ok(())
+        }
+
+        // safe_transfer_from checks that the recieving address has enough balance to satisfy the ExistentialDeposit
+        // This is not quite what it does on Ethereum, but in the same spirit...
+        fn safe_transfer_from(origin, from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> Result {
+            let to_balance = <balances::Module<T>>::free_balance(&to);
+            ensure!(!to_balance.is_zero(), "'to' account does not satisfy the `ExistentialDeposit` requirement");
+
+            Self::transfer_from(origin, from, to, token_id)?;
+
+            Ok(())
+        }
+        // End ERC721 : Public Functions //
+
+        // Not part of ERC721, `transfer_from` that looks up `from` itself
+        // Saves operators from having to pass the current owner, a common client mistake
+        fn operator_transfer(origin, to: T::AccountId, token_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+            let single_use = Self::_check_spender(&owner, &sender, token_id)?;
+            Self::_check_transfer(&owner, &owner, &to, token_id)?;
+
+            Self::_move_token(owner, to, token_id)?;
+            Self::_spend_single_use(token_id, single_use);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, but allows you to play with the runtime
+        fn create_token(origin) -> Result {
+            let sender = ensure_signed(origin)?;
+            let nonce = <Nonce<T>>::get();
+            let random_hash = (<system::Module<T>>::random_seed(), &sender, nonce).using_encoded(T::TokenIdHasher::hash);
+
+            Self::_check_user_mint(&sender, random_hash)?;
+            Self::_charge_mint_price(&sender)?;
+            Self::_mint(sender, random_hash)?;
+            <Nonce<T>>::mutate(|n| *n += 1);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, `approve` for many tokens at once with the same rules
+        // Every token is checked before any approval is written, so the batch fails as a whole
+        fn approve_many(origin, to: T::AccountId, token_ids: Vec<T::Hash>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(token_ids.len() <= Self::max_batch_size() as usize, "Batch too large");
+
+            let mut owners = Vec::with_capacity(token_ids.len());
+            for (i, token_id) in token_ids.iter().enumerate() {
+                let owner = match Self::owner_of(token_id) {
+                    Some(c) => c,
+                    None => return Err("No owner for this token"),
+                };
+
+                ensure!(!token_ids[..i].contains(token_id), "Duplicate token id in batch");
+                ensure!(to != owner, "Owner is implicitly approved");
+                ensure!(sender == owner || Self::is_approved_for_all((owner.clone(), sender.clone())), "You are not allowed to approve for this token");
+                owners.push(owner);
+            }
+
+            for (token_id, owner) in token_ids.into_iter().zip(owners) {
+                <TokenApprovals<T>>::insert(&token_id, &to);
+
+                Self::deposit_event(RawEvent::Approval(owner, to.clone(), token_id));
+            }
+
+            Ok(())
+        }
+
+        // Not part of ERC721, mints a token straight into a marketplace listing
+        // The mint price is only charged once the marketplace accepts the listing, so a refused
+        // listing just undoes the mint and there is nothing to refund
+        // The marketplace is handed a token that already exists, so a refused listing leaves its
+        // mint and burn events (`Transfer`, `Minted`, `Transfer`, `Burned`) in the block
+        fn mint_and_list(origin, token_id: T::Hash, price: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            Self::_check_user_mint(&sender, token_id)?;
+            Self::_check_mint_price(&sender)?;
+            Self::_mint(sender.clone(), token_id)?;
+
+            if let Err(e) = T::MarketplaceHook::list(&sender, &token_id, price) {
+                Self::_revert_mint(&sender, token_id)?;
+                return Err(e);
+            }
+
+            if let Err(e) = Self::_charge_mint_price(&sender) {
+                Self::_revert_mint(&sender, token_id)?;
+                return Err(e);
+            }
+
+            Ok(())
+        }
+
+        // Not part of ERC721, lets a minter pay for a token that goes straight to `recipient`
+        // The minter is recorded as the creator and the mint counts against their cap
+        fn sponsor_mint(origin, recipient: T::AccountId, token_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::is_minter(&sender), "You are not a minter");
+
+            Self::_check_user_mint(&sender, token_id)?;
+            Self::_charge_mint_price(&sender)?;
+            Self::_mint_with_creator(sender, recipient, token_id)?;
+
+            Ok(())
+        }
+
+        // Not part of ERC721, lets a minter such as a bridge mint a token standing for an external asset
+        fn mint_wrapped(origin, token_id: T::Hash, external_ref: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::is_minter(&sender), "You are not a minter");
+            ensure!(!external_ref.is_empty(), "External reference is empty");
+            ensure!(external_ref.len() <= MAX_ASSET_REF_LENGTH, "External reference is too long");
+
+            Self::_check_user_mint(&sender, token_id)?;
+            Self::_charge_mint_price(&sender)?;
+            Self::_mint(sender, token_id)?;
+            <WrappedAsset<T>>::insert(token_id, &external_ref);
+
+            Self::deposit_event(RawEvent::Wrapped(token_id, external_ref));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, mints to the sender and approves e.g. a marketplace in one call
+        fn mint_and_approve(origin, token_id: T::Hash, operator: T::AccountId) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(operator != sender, "Owner is implicitly approved");
+
+            Self::_check_user_mint(&sender, token_id)?;
+            Self::_charge_mint_price(&sender)?;
+            Self::_mint(sender.clone(), token_id)?;
+
+            <TokenApprovals<T>>::insert(&token_id, &operator);
+
+            Self::deposit_event(RawEvent::Approval(sender, operator, token_id));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, approves the module account so escrow features can pull the token in
+        fn approve_escrow(origin, token_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::owner_of(token_id) == Some(sender.clone()), "You do not own this token");
+
+            let escrow = Self::account_id();
+            <TokenApprovals<T>>::insert(&token_id, &escrow);
+            <EscrowDepositor<T>>::insert(token_id, &sender);
+
+            Self::deposit_event(RawEvent::Approval(sender, escrow, token_id));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, puts the sender's token on sale for a fixed price
+        // The module account is approved so `buy` can hand the token over
+        fn list_for_sale(origin, token_id: T::Hash, price: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::owner_of(token_id) == Some(sender.clone()), "You do not own this token");
+
+            ensure!(Self::sale_price(token_id).is_none(), "Token is already listed, use update_price");
+
+            let escrow = Self::account_id();
+            <TokenApprovals<T>>::insert(&token_id, &escrow);
+            <SalePrice<T>>::insert(token_id, price);
+
+            Self::deposit_event(RawEvent::Approval(sender, escrow, token_id));
+            Self::deposit_event(RawEvent::Listed(token_id, price));
+
+            Ok(())
+        }
+
+        fn update_price(origin, token_id: T::Hash, new_price: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::owner_of(token_id) == Some(sender), "You do not own this token");
+            let old_price = match Self::sale_price(token_id) {
+                Some(c) => c,
+                None => return Err("Token is not for sale"),
+            };
+
+            <SalePrice<T>>::insert(token_id, new_price);
+
+            Self::deposit_event(RawEvent::PriceChanged(token_id, old_price, new_price));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, takes the token off sale and withdraws the module account's approval
+        fn delist(origin, token_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::owner_of(token_id) == Some(sender), "You do not own this token");
+            ensure!(Self::sale_price(token_id).is_some(), "Token is not for sale");
+
+            if Self::get_approved(token_id) == Some(Self::account_id()) {
+                Self::_clear_approval(token_id)?;
+            }
+            Self::_delist(token_id);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, pays the listed price to the owner and takes the token
+        // `max_price` protects the buyer from an `update_price` made before the purchase
+        fn buy(origin, token_id: T::Hash, max_price: T::Balance) -> Result {
+            let buyer = ensure_signed(origin)?;
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+            let price = match Self::sale_price(token_id) {
+                Some(c) => c,
+                None => return Err("Token is not for sale"),
+            };
+
+            ensure!(price <= max_price, "Price is above your maximum");
+            ensure!(buyer != owner, "You already own this token");
+            ensure!(Self::_is_approved_or_owner(&owner, &Self::account_id(), token_id), "Listing is no longer approved");
+            Self::_check_transfer(&owner, &owner, &buyer, token_id)?;
+
+            // The fee rounds down, and the seller gets the rest, so the two always add up to the price
+            let fee = Self::_marketplace_fee(price)?;
+            let payout = price - fee;
+
+            // Both payments are checked before the first one is made, including their transfer fees
+            // and the existential deposit of a recipient without an account
+            let fee_liability = if fee.is_zero() { Zero::zero() } else { Self::_payment_liability(&buyer, &Self::account_id(), fee)? };
+            let payout_liability = if payout.is_zero() { Zero::zero() } else { Self::_payment_liability(&buyer, &owner, payout)? };
+            let liability = match fee_liability.checked_add(&payout_liability) {
+                Some(c) => c,
+                None => return Err("Overflow computing the cost of this purchase"),
+            };
+            let free_balance = <balances::Module<T>>::free_balance(&buyer);
+            ensure!(free_balance >= liability, "Not enough free balance to buy this token");
+            // Paying the fee must not leave the buyer below the existential deposit, which would
+            // remove their account before the seller is paid
+            if !fee.is_zero() && !payout.is_zero() {
+                ensure!(free_balance - fee_liability >= <balances::Module<T>>::existential_deposit(), "Not enough free balance to buy this token");
+            }
+
+            if !fee.is_zero() {
+                <balances::Module<T>>::make_transfer(&buyer, &Self::account_id(), fee)?;
+            }
+            if !payout.is_zero() {
+                <balances::Module<T>>::make_transfer(&buyer, &owner, payout)?;
+            }
+            Self::_move_token(owner, buyer, token_id)?;
+
+            Ok(())
+        }
+
+        // Not part of ERC721, offers `amount` for a token, reserving it until accepted or withdrawn
+        fn make_offer(origin, token_id: T::Hash, amount: T::Balance) -> Result {
+            let buyer = ensure_signed(origin)?;
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+
+            ensure!(buyer != owner, "You already own this token");
+            ensure!(!amount.is_zero(), "Offer must be more than zero");
+            ensure!(Self::offer_of((token_id, buyer.clone())).is_zero(), "Withdraw your current offer first");
+            ensure!(Self::offer_bidders(token_id).len() < MAX_OFFERS_PER_TOKEN, "Token has too many open offers");
+
+            <balances::Module<T>>::reserve(&buyer, amount)?;
+            <Offers<T>>::insert((token_id, buyer.clone()), amount);
+            <OfferBidders<T>>::mutate(token_id, |bidders| bidders.push(buyer.clone()));
+
+            let is_highest = match Self::highest_offer(token_id) {
+                Some((_, highest)) => amount > highest,
+                None => true,
+            };
+            if is_highest {
+                <HighestOffer<T>>::insert(token_id, (buyer, amount));
+            }
+
+            Ok(())
+        }
+
+        fn withdraw_offer(origin, token_id: T::Hash) -> Result {
+            let buyer = ensure_signed(origin)?;
+            let amount = Self::offer_of((token_id, buyer.clone()));
+            ensure!(!amount.is_zero(), "You have no offer for this token");
+
+            Self::_remove_offer(token_id, &buyer);
+            <balances::Module<T>>::unreserve(&buyer, amount);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, sells the token to `buyer` for their reserved offer
+        fn accept_offer(origin, token_id: T::Hash, buyer: T::AccountId) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::owner_of(token_id) == Some(sender.clone()), "You do not own this token");
+
+            let amount = Self::offer_of((token_id, buyer.clone()));
+            ensure!(!amount.is_zero(), "This account has no offer for this token");
+            Self::_check_transfer(&sender, &sender, &buyer, token_id)?;
+
+            // The bidder's reserve can shrink after the offer, e.g. through a slash, and
+            // `repatriate_reserved` would then only move part of the offer
+            ensure!(<balances::Module<T>>::reserved_balance(&buyer) >= amount, "Offer is no longer fully reserved");
+
+            if let Some(_shortfall) = <balances::Module<T>>::repatriate_reserved(&buyer, &sender, amount)? {
+                return Err("Offer is no longer fully reserved");
+            }
+            Self::_remove_offer(token_id, &buyer);
+            Self::_move_token(sender, buyer, token_id)?;
+
+            Ok(())
+        }
+
+        // Not part of ERC721, a transfer carrying a note for reconciliation
+        // The memo is not stored, it only lives in the emitted event
+        fn transfer_with_memo(origin, to: T::AccountId, token_id: T::Hash, memo: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(memo.len() <= MAX_MEMO_LENGTH, "Memo is too long");
+
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+            let single_use = Self::_check_spender(&owner, &sender, token_id)?;
+            Self::_check_transfer(&owner, &owner, &to, token_id)?;
+
+            Self::_move_token(owner.clone(), to.clone(), token_id)?;
+            Self::_spend_single_use(token_id, single_use);
+
+            Self::deposit_event(RawEvent::TransferWithMemo(owner, to, token_id, memo));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, restricts where the owner's token can be sent
+        fn add_whitelisted_recipient(origin, token_id: T::Hash, recipient: T::AccountId) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::owner_of(token_id) == Some(sender), "You do not own this token");
+            ensure!(!Self::is_whitelisted_recipient((token_id, recipient.clone())), "Recipient is already whitelisted");
+            ensure!(Self::whitelisted_recipients(token_id).len() < MAX_RECIPIENTS_PER_TOKEN, "Token has too many whitelisted recipients");
+
+            <TokenRecipientWhitelist<T>>::insert((token_id, recipient.clone()), true);
+            <WhitelistedRecipients<T>>::mutate(token_id, |recipients| recipients.push(recipient));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, removing the last recipient makes the token freely transferable again
+        fn remove_whitelisted_recipient(origin, token_id: T::Hash, recipient: T::AccountId) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::owner_of(token_id) == Some(sender), "You do not own this token");
+            ensure!(Self::is_whitelisted_recipient((token_id, recipient.clone())), "Recipient is not whitelisted");
+
+            <TokenRecipientWhitelist<T>>::remove((token_id, recipient.clone()));
+            <WhitelistedRecipients<T>>::mutate(token_id, |recipients| recipients.retain(|r| *r != recipient));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, settles a transfer order from an off-chain order book
+        // `from` signs `(ORDER_DOMAIN, to, token_id, deadline)`, and anyone may submit it once before the deadline
+        fn execute_order(origin, from: T::AccountId, to: T::AccountId, token_id: T::Hash, deadline: T::BlockNumber, signature: Vec<u8>) -> Result {
+            ensure_signed(origin)?;
+            ensure!(<system::Module<T>>::block_number() <= deadline, "Order has expired");
+
+            let payload = (ORDER_DOMAIN, &to, token_id, deadline).encode();
+            let order_id = (&from, &payload).using_encoded(<T as system::Trait>::Hashing::hash);
+            ensure!(!Self::is_order_used(order_id), "Order has already been executed");
+
+            Self::_verify_signature(&from, &payload, signature)?;
+
+            Self::_transfer_from(from, to, token_id)?;
+            <UsedOrders<T>>::insert(order_id, true);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, an off-chain agreed sale settled in one call by the buyer
+        // The seller signs `(EXCHANGE_DOMAIN, token_id, price, buyer, deadline)`, and each signed sale can
+        // only be settled once before the deadline, which is also how a seller lets an unwanted sale lapse
+        fn exchange(origin, token_id: T::Hash, seller: T::AccountId, price: T::Balance, deadline: T::BlockNumber, seller_sig: Vec<u8>) -> Result {
+            let buyer = ensure_signed(origin)?;
+            ensure!(buyer != seller, "You already own this token");
+            ensure!(<system::Module<T>>::block_number() <= deadline, "Order has expired");
+
+            let payload = (EXCHANGE_DOMAIN, token_id, price, &buyer, deadline).encode();
+            let order_id = (&seller, &payload).using_encoded(<T as system::Trait>::Hashing::hash);
+            ensure!(!Self::is_order_used(order_id), "Order has already been executed");
+
+            Self::_verify_signature(&seller, &payload, seller_sig)?;
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+            Self::_check_transfer(&owner, &seller, &buyer, token_id)?;
+
+            // As in `buy`, the payment is checked with its transfer fee and the existential deposit
+            // of a seller without an account before it is made
+            if !price.is_zero() {
+                let liability = Self::_payment_liability(&buyer, &seller, price)?;
+                ensure!(<balances::Module<T>>::free_balance(&buyer) >= liability, "Not enough free balance to buy this token");
+
+                <balances::Module<T>>::make_transfer(&buyer, &seller, price)?;
+            }
+            Self::_move_token(seller, buyer, token_id)?;
+            <UsedOrders<T>>::insert(order_id, true);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, applies every transfer it can and skips the rest
+        fn transfer_many_best_effort(origin, transfers: Vec<(T::AccountId, T::AccountId, T::Hash)>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(transfers.len() <= Self::max_batch_size() as usize, "Batch too large");
+
+            let mut succeeded = 0;
+            let mut failed = 0;
+            for (from, to, token_id) in transfers {
+                match Self::try_transfer(sender.clone(), from, to, token_id) {
+                    TransferOutcome::Success => succeeded += 1,
+                    _ => failed += 1,
+                }
+            }
+
+            Self::deposit_event(RawEvent::BatchPartial(succeeded, failed));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, burns the sender's token for a refund out of the module account
+        fn burn_for_refund(origin, token_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::owner_of(token_id) == Some(sender.clone()), "You do not own this token");
+
+            ensure!(Self::token_locks(token_id).is_empty(), "Token is locked");
+
+            let refund = Self::refund_amount();
+            ensure!(!refund.is_zero(), "Refunds are not enabled");
+
+            // The refund and its transfer fee must leave the pool at the existential deposit,
+            // a pool that falls below it would be removed with whatever it still holds
+            let pool = Self::account_id();
+            let liability = Self::_payment_liability(&pool, &sender, refund)?;
+            let pool_balance = <balances::Module<T>>::free_balance(&pool);
+            ensure!(pool_balance >= liability, "Refund pool is depleted");
+            ensure!(pool_balance - liability >= <balances::Module<T>>::existential_deposit(), "Refund pool is depleted");
+
+            // Paid before the burn, so a failed refund leaves the token with its owner
+            <balances::Module<T>>::make_transfer(&pool, &sender, refund)?;
+            Self::_burn(token_id)?;
+
+            Ok(())
+        }
+
+        // Not part of ERC721, ties several of the sender's tokens together so they only move as a set
+        fn create_bundle(origin, token_ids: Vec<T::Hash>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(!token_ids.is_empty(), "Bundle has no tokens");
+            ensure!(token_ids.len() <= Self::max_batch_size() as usize, "Batch too large");
+
+            for (i, token_id) in token_ids.iter().enumerate() {
+                ensure!(Self::owner_of(token_id) == Some(sender.clone()), "You do not own this token");
+                ensure!(Self::bundle_of(token_id).is_none(), "Token is already in a bundle");
+                ensure!(!token_ids[..i].contains(token_id), "Duplicate token id in batch");
+            }
+
+            let bundle_id = Self::next_bundle_id();
+            let next_bundle_id = match bundle_id.checked_add(1) {
+                Some(c) => c,
+                None => return Err("Overflow creating a new bundle"),
+            };
+
+            for token_id in token_ids.iter() {
+                <TokenBundle<T>>::insert(token_id, bundle_id);
+            }
+            <Bundles<T>>::insert(bundle_id, token_ids);
+            <NextBundleId<T>>::put(next_bundle_id);
+
+            Self::deposit_event(RawEvent::BundleCreated(sender, bundle_id));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, moves every token of a bundle, or none of them
+        fn transfer_bundle(origin, bundle_id: u32, to: T::AccountId) -> Result {
+            let sender = ensure_signed(origin)?;
+            let token_ids = Self::bundle_tokens(bundle_id);
+            let owner = match token_ids.first().and_then(|token_id| Self::owner_of(token_id)) {
+                Some(c) => c,
+                None => return Err("Bundle does not exist"),
+            };
+            // Tokens held by the module account can only leave through `reclaim`, one at a time
+            ensure!(to != Self::account_id(), "Bundles cannot be sent to the module account");
+
+            let mut single_use = Vec::new();
+            for token_id in token_ids.iter() {
+                let member_owner = match Self::owner_of(token_id) {
+                    Some(c) => c,
+                    None => return Err("No owner for this token"),
+                };
+                single_use.push(Self::_check_spender(&member_owner, &sender, *token_id)?);
+                Self::_check_transfer_rules(&member_owner, &owner, &to, *token_id)?;
+            }
+
+            for (token_id, single_use) in token_ids.into_iter().zip(single_use) {
+                Self::_move_token(owner.clone(), to.clone(), token_id)?;
+                Self::_spend_single_use(token_id, single_use);
+            }
+
+            Self::deposit_event(RawEvent::BundleTransferred(owner, to, bundle_id));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, makes the tokens of a bundle individually transferable again
+        fn dissolve_bundle(origin, bundle_id: u32) -> Result {
+            let sender = ensure_signed(origin)?;
+            let token_ids = Self::bundle_tokens(bundle_id);
+            let owner = match token_ids.first().and_then(|token_id| Self::owner_of(token_id)) {
+                Some(c) => c,
+                None => return Err("Bundle does not exist"),
+            };
+            ensure!(owner == sender, "You do not own this bundle");
+
+            for token_id in token_ids {
+                <TokenBundle<T>>::remove(token_id);
+            }
+            <Bundles<T>>::remove(bundle_id);
+
+            Self::deposit_event(RawEvent::BundleDissolved(sender, bundle_id));
+
+            Ok(())
+        }
+
+        // Not part of ERC721, a transfer the recipient agreed to off-chain
+        // `to` signs `(CONSENT_DOMAIN, token_id, owner, nonce)` with its current `nonce_of`,
+        // so a consent only works for one transfer from that owner
+        fn transfer_with_consent(origin, to: T::AccountId, token_id: T::Hash, nonce: u64, consent_sig: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+            let owner = match Self::owner_of(token_id) {
+                Some(c) => c,
+                None => return Err("No owner for this token"),
+            };
+            let single_use = Self::_check_spender(&owner, &sender, token_id)?;
+
+            let payload = (CONSENT_DOMAIN, token_id, &owner, nonce).encode();
+            Self::_verify_signature(&to, &payload, consent_sig)?;
+            ensure!(Self::nonce_of(&to) == nonce, "Nonce is stale or already used");
+            Self::_check_transfer(&owner, &owner, &to, token_id)?;
+
+            Self::consume_nonce(&to, nonce)?;
+            Self::_move_token(owner, to, token_id)?;
+            Self::_spend_single_use(token_id, single_use);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, revokes every operator the sender approved, e.g. after account recovery
+        fn reset_all_approvals(origin) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            for operator in <ApprovedOperators<T>>::take(&sender) {
+                <OperatorApprovals<T>>::remove((sender.clone(), operator.clone()));
+
+                Self::deposit_event(RawEvent::ApprovalForAll(sender.clone(), operator, false));
+            }
+
+            Ok(())
+        }
+
+        // Not part of ERC721, burns several of the sender's tokens in one call
+        // Every token is checked before anything is burned, so the batch fails as a whole
+        fn burn_many(origin, token_ids: Vec<T::Hash>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(token_ids.len() <= Self::max_batch_size() as usize, "Batch too large");
+
+            for (i, token_id) in token_ids.iter().enumerate() {
+                ensure!(Self::owner_of(token_id) == Some(sender.clone()), "You do not own this token");
+                ensure!(Self::token_locks(token_id).is_empty(), "Token is locked");
+                ensure!(!token_ids[..i].contains(token_id), "Duplicate token id in batch");
+            }
+
+            for token_id in token_ids {
+                Self::_burn(token_id)?;
+            }
+
+            Ok(())
+        }
+
+        // Not part of ERC721, `None` removes the per creator cap
+        fn set_per_creator_cap(origin, cap: Option<u32>) -> Result {
+            ensure_root(origin)?;
+            match cap {
+                Some(c) => <PerCreatorCap<T>>::put(c),
+                None => <PerCreatorCap<T>>::kill(),
+            }
+
+            Ok(())
+        }
+
+        fn set_burn_refunds_cap(origin, enabled: bool) -> Result {
+            ensure_root(origin)?;
+            <BurnRefundsCap<T>>::put(enabled);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, `None` keeps minting open at every block
+        fn set_mint_window(origin, window: Option<(T::BlockNumber, T::BlockNumber)>) -> Result {
+            ensure_root(origin)?;
+            match window {
+                Some(w) => {
+                    ensure!(w.0 <= w.1, "Mint window ends before it starts");
+                    <MintWindow<T>>::put(w);
+                },
+                None => <MintWindow<T>>::kill(),
+            }
+
+            Ok(())
+        }
+
+        // Not part of ERC721, `None` makes minting free
+        fn set_mint_price(origin, price: Option<T::Balance>) -> Result {
+            ensure_root(origin)?;
+            match price {
+                Some(p) => <MintPrice<T>>::put(p),
+                None => <MintPrice<T>>::kill(),
+            }
+
+            Ok(())
+        }
+
+        // Not part of ERC721, the admin mint path, which may also use reserved ids
+        // It ignores the creator cap and records no creator. Like every mint path it is closed outside the mint window
+        fn mint(origin, to: T::AccountId, token_id: T::Hash) -> Result {
+            ensure_root(origin)?;
+            Self::_check_mint_window()?;
+            Self::_mint_token(None, to, token_id)?;
+
+            Ok(())
+        }
+
+        fn set_minter(origin, who: T::AccountId, enabled: bool) -> Result {
+            ensure_root(origin)?;
+            <Minters<T>>::insert(&who, enabled);
 
-        fn deposit_event<T>() = default;
+            Ok(())
+        }
 
-        // Start ERC721 : Public Functions //
-        fn approve(origin, to: T::AccountId, token_id: T::Hash) -> Result {
-            let sender = ensure_signed(origin)?;
-            let owner = match Self::owner_of(token_id) {
+        // Not part of ERC721, keeps an id (e.g. a "genesis" token) for the admin
+        fn reserve_token_id(origin, token_id: T::Hash) -> Result {
+            ensure_root(origin)?;
+            ensure!(!Self::_exists(token_id), "Token already exists");
+            <ReservedTokens<T>>::insert(token_id, true);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, reissues a token under a new id, e.g. after the id scheme changes
+        // Owner, approval, enumeration and every other per token entry move with it
+        fn remap_token_id(origin, old_id: T::Hash, new_id: T::Hash) -> Result {
+            ensure_root(origin)?;
+            let owner = match Self::owner_of(old_id) {
                 Some(c) => c,
                 None => return Err("No owner for this token"),
             };
+            ensure!(!Self::_exists(new_id), "Token already exists");
+
+            // Writing to storage begins here
+            <TokenOwner<T>>::remove(old_id);
+            <TokenOwner<T>>::insert(new_id, &owner);
+
+            let all_tokens_index = <AllTokensIndex<T>>::take(old_id);
+            <AllTokensIndex<T>>::insert(new_id, all_tokens_index);
+            <AllTokens<T>>::insert(all_tokens_index, new_id);
+
+            let owned_tokens_index = <OwnedTokensIndex<T>>::take(old_id);
+            <OwnedTokensIndex<T>>::insert(new_id, owned_tokens_index);
+            <OwnedTokens<T>>::insert((owner, owned_tokens_index), new_id);
+
+            if let Some(approved) = <TokenApprovals<T>>::take(old_id) {
+                <TokenApprovals<T>>::insert(new_id, approved);
+            }
+            if let Some(spender) = <SingleUseApproval<T>>::take(old_id) {
+                <SingleUseApproval<T>>::insert(new_id, spender);
+            }
+            if let Some(creator) = <TokenCreator<T>>::take(old_id) {
+                <TokenCreator<T>>::insert(new_id, creator);
+            }
+            if let Some(external_ref) = <WrappedAsset<T>>::take(old_id) {
+                <WrappedAsset<T>>::insert(new_id, external_ref);
+            }
+            if let Some(depositor) = <EscrowDepositor<T>>::take(old_id) {
+                <EscrowDepositor<T>>::insert(new_id, depositor);
+            }
+            if let Some(price) = <SalePrice<T>>::take(old_id) {
+                <SalePrice<T>>::insert(new_id, price);
+            }
+            if let Some(offer) = <HighestOffer<T>>::take(old_id) {
+                <HighestOffer<T>>::insert(new_id, offer);
+            }
+            if let Some(bundle_id) = <TokenBundle<T>>::take(old_id) {
+                <TokenBundle<T>>::insert(new_id, bundle_id);
+                <Bundles<T>>::mutate(bundle_id, |tokens| for t in tokens.iter_mut() {
+                    if *t == old_id {
+                        *t = new_id;
+                    }
+                });
+            }
+
+            if let Some(block) = <MintBlock<T>>::take(old_id) {
+                <MintBlock<T>>::insert(new_id, block);
+                <MintedInBlock<T>>::mutate(block, |tokens| for t in tokens.iter_mut() {
+                    if *t == old_id {
+                        *t = new_id;
+                    }
+                });
+            }
+
+            // Only accounts in the token's ownership history can have it in their transfer records
+            let history = <OwnershipHistory<T>>::take(old_id);
+            let mut visited: Vec<&T::AccountId> = Vec::new();
+            for account in history.iter() {
+                if visited.contains(&account) {
+                    continue;
+                }
+                visited.push(account);
+
+                if Self::sent_transfers(account).contains(&old_id) {
+                    <SentTransfers<T>>::mutate(account, |tokens| for t in tokens.iter_mut() {
+                        if *t == old_id {
+                            *t = new_id;
+                        }
+                    });
+                }
+                if Self::received_transfers(account).contains(&old_id) {
+                    <ReceivedTransfers<T>>::mutate(account, |tokens| for t in tokens.iter_mut() {
+                        if *t == old_id {
+                            *t = new_id;
+                        }
+                    });
+                }
+            }
+            if !history.is_empty() {
+                <OwnershipHistory<T>>::insert(new_id, history);
+            }
+
+            let locks = <TokenLocks<T>>::take(old_id);
+            if !locks.is_empty() {
+                <TokenLocks<T>>::insert(new_id, locks);
+            }
+
+            let recipients = <WhitelistedRecipients<T>>::take(old_id);
+            for recipient in recipients.iter() {
+                <TokenRecipientWhitelist<T>>::remove((old_id, recipient.clone()));
+                <TokenRecipientWhitelist<T>>::insert((new_id, recipient.clone()), true);
+            }
+            if !recipients.is_empty() {
+                <WhitelistedRecipients<T>>::insert(new_id, recipients);
+            }
+
+            let bidders = <OfferBidders<T>>::take(old_id);
+            for bidder in bidders.iter() {
+                let amount = <Offers<T>>::take((old_id, bidder.clone()));
+                <Offers<T>>::insert((new_id, bidder.clone()), amount);
+            }
+            if !bidders.is_empty() {
+                <OfferBidders<T>>::insert(new_id, bidders);
+            }
+
+            Self::deposit_event(RawEvent::TokenRemapped(old_id, new_id));
 
-            ensure!(to != owner, "Owner is implicitly approved");
-            ensure!(sender == owner || Self::is_approved_for_all((owner.clone(), sender.clone())), "You are not allowed to approve for this token");
+            Ok(())
+        }
 
-            <TokenApprovals<T>>::insert(&token_id, &to);
+        // Not part of ERC721, lets governance drop a stuck or compromised token approval
+        fn admin_clear_approval(origin, token_id: T::Hash) -> Result {
+            ensure_root(origin)?;
+            ensure!(Self::_exists(token_id), "No owner for this token");
 
-            Self::deposit_event(RawEvent::Approval(owner, to, token_id));
+            Self::_clear_approval(token_id)?;
+            <SingleUseApproval<T>>::remove(token_id);
+
+            Self::deposit_event(RawEvent::ApprovalCleared(token_id));
 
             Ok(())
         }
 
-        fn set_approval_for_all(origin, to: T::AccountId, approved: bool) -> Result {
-            let sender = ensure_signed(origin)?;
-            ensure!(to != sender, "You are already implicity approved for your own actions");
-            <OperatorApprovals<T>>::insert((sender.clone(), to.clone()), approved);
+        // Not part of ERC721, lets governance clear out an account's tokens
+        // Burns at most `max_batch_size` tokens per call, so large holdings take repeated calls
+        fn burn_all_of(origin, owner: T::AccountId) -> Result {
+            ensure_root(origin)?;
+            let balance = Self::balance_of(&owner);
+            let to_burn = balance.min(Self::max_batch_size() as u64);
+
+            // The tokens to burn are checked first, so a locked one fails the call before any burn
+            for index in (balance - to_burn)..balance {
+                let token_id = Self::token_of_owner_by_index((owner.clone(), index));
+                ensure!(Self::token_locks(token_id).is_empty(), "Token is locked");
+            }
+
+            // Always burn the last owned token so nothing has to be moved down in `OwnedTokens`
+            for _ in 0..to_burn {
+                let last_index = Self::balance_of(&owner) - 1;
+                let token_id = Self::token_of_owner_by_index((owner.clone(), last_index));
+                Self::_burn(token_id)?;
+            }
 
-            Self::deposit_event(RawEvent::ApprovalForAll(sender, to, approved));
+            Ok(())
+        }
+
+        // Not part of ERC721, recovers a token that was sent to the module account by mistake
+        fn reclaim(origin, token_id: T::Hash, to: T::AccountId) -> Result {
+            ensure_root(origin)?;
+            let module_account = Self::account_id();
+            ensure!(Self::owner_of(token_id) == Some(module_account.clone()), "Token is not held by the module account");
+
+            // A bundled token can only reach the module account through a `transfer_bundle` made
+            // before bundles were kept out of it, and it leaves its bundle here
+            Self::_check_transfer_rules(&module_account, &module_account, &to, token_id)?;
+            Self::_remove_from_bundle(token_id);
+            Self::_move_token(module_account, to.clone(), token_id)?;
+            // An approval set while the module held the token must not follow it to `to`,
+            // even when `ClearApprovalOnTransfer` is off
+            Self::_clear_approval(token_id)?;
+
+            Self::deposit_event(RawEvent::Reclaimed(token_id, to));
 
             Ok(())
         }
 
-        // transfer_from will transfer to addresses even without a balance
-        fn transfer_from(origin, from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> Result {
+        // Not part of ERC721, records every current holder's balance under `block_tag`
+        // Reads each holder once, so its cost grows with `holder_count`. A tag can only be used once
+        fn snapshot_holders(origin, block_tag: u32) -> Result {
+            ensure_root(origin)?;
+            ensure!(!Self::is_snapshot_taken(block_tag), "A snapshot with this tag already exists");
+
+            for index in 0..Self::holder_count() {
+                let holder = Self::holder_by_index(index);
+                let balance = Self::balance_of(&holder).min(u32::max_value() as u64) as u32;
+                <HolderSnapshot<T>>::insert((block_tag, holder), balance);
+            }
+            <SnapshotTaken<T>>::insert(block_tag, true);
+
+            Self::deposit_event(RawEvent::SnapshotTaken(block_tag));
+
+            Ok(())
+        }
+
+        fn set_locker(origin, who: T::AccountId, enabled: bool) -> Result {
+            ensure_root(origin)?;
+            <Lockers<T>>::insert(&who, enabled);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, locks a token for `purpose` without touching other purposes' locks
+        fn add_lock(origin, token_id: T::Hash, purpose: Vec<u8>) -> Result {
             let sender = ensure_signed(origin)?;
-            ensure!(Self::_is_approved_or_owner(sender, token_id), "You do not own this token");
+            ensure!(Self::is_locker(&sender), "You are not a locker");
+            ensure!(Self::_exists(token_id), "No owner for this token");
+            ensure!(purpose.len() <= MAX_LOCK_PURPOSE_LENGTH, "Lock purpose is too long");
 
-            Self::_transfer_from(from, to, token_id)?;
+            let mut locks = Self::token_locks(token_id);
+            ensure!(!locks.contains(&purpose), "Token is already locked for this purpose");
+            ensure!(locks.len() < MAX_LOCKS_PER_TOKEN, "Token has too many locks");
+
+            locks.push(purpose);
+            <TokenLocks<T>>::insert(token_id, locks);
 
             Ok(())
         }
 
-        // safe_transfer_from checks that the recieving address has enough balance to satisfy the ExistentialDeposit
-        // This is not quite what it does on Ethereum, but in the same spirit...
-        fn safe_transfer_from(origin, from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> Result {
-            let to_balance = <balances::Module<T>>::free_balance(&to);
-            ensure!(!to_balance.is_zero(), "'to' account does not satisfy the `ExistentialDeposit` requirement");
+        fn remove_lock(origin, token_id: T::Hash, purpose: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::is_locker(&sender), "You are not a locker");
 
-            Self::transfer_from(origin, from, to, token_id)?;
+            let mut locks = Self::token_locks(token_id);
+            ensure!(locks.contains(&purpose), "Token is not locked for this purpose");
+
+            locks.retain(|p| *p != purpose);
+            <TokenLocks<T>>::insert(token_id, locks);
 
             Ok(())
         }
-        // End ERC721 : Public Functions //
 
-        // Not part of ERC721, but allows you to play with the runtime
-        fn create_token(origin) -> Result {
+        // Not part of ERC721, lets governance bless an operator (e.g. a marketplace) for all owners
+        fn set_default_operator(origin, operator: T::AccountId, enabled: bool) -> Result {
+            ensure_root(origin)?;
+            <DefaultOperators<T>>::insert(&operator, enabled);
+
+            Ok(())
+        }
+
+        // Not part of ERC721, opts the sender out of a default operator's blanket approval
+        fn revoke_default_operator(origin, operator: T::AccountId) -> Result {
             let sender = ensure_signed(origin)?;
-            let nonce = <Nonce<T>>::get();
-            let random_hash = (<system::Module<T>>::random_seed(), &sender, nonce).using_encoded(<T as system::Trait>::Hashing::hash);
-            
-            Self::_mint(sender, random_hash)?;
-            <Nonce<T>>::mutate(|n| *n += 1);
+            ensure!(Self::is_default_operator(&operator), "This account is not a default operator");
+            <DefaultOperatorRevoked<T>>::insert((sender, operator), true);
 
             Ok(())
         }
@@ -115,34 +1275,264 @@ decl_module! {
 }
 
 impl<T: Trait> Module<T> {
-    // Start ERC721 : Internal Functions //
-    fn _exists(token_id: T::Hash) -> bool {
-        return <TokenOwner<T>>::exists(token_id);
+    // Not part of ERC721, but gives escrow-like features an account to hold tokens
+    // Nobody holds the key to this account, so only this module can move tokens out of it
+    pub fn account_id() -> T::AccountId {
+        let seed = MODULE_ID.using_encoded(<T as system::Trait>::Hashing::hash);
+        <T::AccountId as Decode>::decode(&mut &seed.encode()[..]).unwrap_or_default()
+    }
+
+    // Not part of ERC721, lets other modules check whether `spender` may move a token
+    // Uses the same approval check as the transfer calls, so single use approvals count too
+    pub fn is_approved_or_owner(spender: &T::AccountId, token_id: T::Hash) -> bool {
+        match Self::owner_of(token_id) {
+            Some(owner) => Self::_check_spender(&owner, spender, token_id).is_ok(),
+            None => false,
+        }
     }
 
-    fn _is_approved_or_owner(spender: T::AccountId, token_id: T::Hash) -> bool {
-        let owner = Self::owner_of(token_id);
-        let approved_user = Self::get_approved(token_id);
+    // Not part of ERC721, owner and approved account of a token in one read
+    pub fn token_info(token_id: T::Hash) -> Option<(T::AccountId, Option<T::AccountId>)> {
+        Self::owner_of(token_id).map(|owner| (owner, Self::get_approved(token_id)))
+    }
 
-        let approved_as_owner = match owner {
-            Some(ref o) => o == &spender,
+    // Not part of ERC721, whether a token held by its owner could move right now
+    // Runs the bundle check of `_check_transfer` and every rule that does not depend on the
+    // recipient. The transfer policy and recipient whitelist do, so those can still refuse a transfer
+    pub fn is_transferable(token_id: T::Hash) -> bool {
+        match Self::owner_of(token_id) {
+            Some(owner) => Self::bundle_of(token_id).is_none() && Self::_check_sender_rules(&owner, &owner, token_id).is_ok(),
             None => false,
+        }
+    }
+
+    // Not part of ERC721, for gating features on holding any token of this collection
+    pub fn holds_any(who: &T::AccountId) -> bool {
+        Self::balance_of(who) > 0
+    }
+
+    // Not part of ERC721, every token of `owner` in enumeration order, for migration tooling
+    // Reads exactly `balance_of(owner)` entries of `OwnedTokens`, so it stays cheap for accounts without tokens
+    pub fn owned_tokens_raw(owner: &T::AccountId) -> Vec<T::Hash> {
+        (0..Self::balance_of(owner))
+            .map(|index| Self::token_of_owner_by_index((owner.clone(), index)))
+            .collect()
+    }
+
+    // Not part of ERC721, up to `MAX_PAGE_SIZE` current holders starting at `start`
+    pub fn holders_paged(start: u64, limit: u64) -> Vec<T::AccountId> {
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(Self::holder_count());
+        (start..end).map(Self::holder_by_index).collect()
+    }
+
+    // Not part of ERC721, up to `MAX_PAGE_SIZE` tokens of `AllTokens` starting at `start`
+    pub fn all_tokens_paged(start: u32, limit: u32) -> Vec<T::Hash> {
+        let end = (start as u64).saturating_add((limit as u64).min(MAX_PAGE_SIZE)).min(Self::total_supply());
+        (start as u64..end).map(Self::token_by_index).collect()
+    }
+
+    // Not part of ERC721, the tokens at `AllTokens` indexes `start..end`, for resumable indexing
+    // Spans over `MAX_PAGE_SIZE` are cut short, and indexes past `total_supply` are skipped
+    pub fn tokens_in_range(start: u32, end: u32) -> Vec<T::Hash> {
+        let end = (end as u64).min((start as u64).saturating_add(MAX_PAGE_SIZE)).min(Self::total_supply());
+        (start as u64..end).map(Self::token_by_index).collect()
+    }
+
+    // Not part of ERC721, counts the tokens among the first `limit` of `AllTokens` that match `pred`
+    // Never visits more than `MAX_SCAN_LENGTH` tokens, whatever `limit` is
+    pub fn count_where<F: Fn(&T::Hash) -> bool>(pred: F, limit: u32) -> u32 {
+        let end = (limit as u64).min(MAX_SCAN_LENGTH).min(Self::total_supply());
+        (0..end)
+            .map(Self::token_by_index)
+            .filter(|token_id| pred(token_id))
+            .count() as u32
+    }
+
+    // Not part of ERC721, used by calls carrying an off-chain signature so each one
+    // can only be submitted once. Relayers read the expected value from `nonce_of`
+    pub fn consume_nonce(who: &T::AccountId, expected: u64) -> Result {
+        let nonce = Self::nonce_of(who);
+        ensure!(nonce == expected, "Nonce is stale or already used");
+
+        let new_nonce = match nonce.checked_add(1) {
+            Some(c) => c,
+            None => return Err("Overflow incrementing account nonce"),
         };
 
-        let approved_as_delegate = match owner {
-            Some(d) => Self::is_approved_for_all((d, spender.clone())),
-            None => false,
+        <AccountNonce<T>>::insert(who, new_nonce);
+
+        Ok(())
+    }
+
+    // Not part of ERC721, moves a token approved with `approve_escrow` into the module account
+    pub fn pull_into_escrow(token_id: T::Hash) -> Result {
+        let depositor = match Self::escrow_depositor(token_id) {
+            Some(c) => c,
+            None => return Err("Token has not been approved for escrow"),
         };
 
-        let approved_as_user = match approved_user {
-            Some(u) => u == spender,
-            None => false,
+        let owner = match Self::owner_of(token_id) {
+            Some(c) => c,
+            None => return Err("No owner for this token"),
+        };
+
+        let escrow = Self::account_id();
+        ensure!(Self::_is_approved_or_owner(&owner, &escrow, token_id), "Escrow is not approved for this token");
+        Self::_check_transfer(&owner, &depositor, &escrow, token_id)?;
+
+        Self::_move_token(depositor, escrow, token_id)
+    }
+
+    // Not part of ERC721, the owner of a token and the trie key its `TokenOwner` entry is stored
+    // under, so a light client can request a storage proof of ownership for that key
+    pub fn owner_proof(token_id: T::Hash) -> Option<(T::AccountId, Vec<u8>)> {
+        Self::owner_of(token_id).map(|owner| {
+            // Runtime storage hashes every map key with twox_128 before it reaches the trie
+            let storage_key = runtime_io::twox_128(&<TokenOwner<T>>::key_for(&token_id)).to_vec();
+            (owner, storage_key)
+        })
+    }
+
+    // Not part of ERC721, `transfer_from` for batch workflows that must not abort
+    // Nothing is written unless the outcome is `Success`
+    pub fn try_transfer(spender: T::AccountId, from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> TransferOutcome {
+        let owner = match Self::owner_of(token_id) {
+            Some(c) => c,
+            None => return TransferOutcome::NoToken,
+        };
+        if owner != from {
+            return TransferOutcome::NotOwner;
+        }
+
+        let single_use = match Self::_check_spender(&owner, &spender, token_id) {
+            Ok(c) => c,
+            Err(_) => return TransferOutcome::NotApproved,
         };
 
-        return approved_as_owner || approved_as_user || approved_as_delegate
+        match Self::_check_transfer(&owner, &from, &to, token_id).and_then(|_| Self::_move_token(from, to, token_id)) {
+            Ok(()) => {
+                Self::_spend_single_use(token_id, single_use);
+                TransferOutcome::Success
+            },
+            Err(_) => TransferOutcome::Rejected,
+        }
+    }
+
+    // Not part of ERC721, runs the checks of a mint by `minter` to `to` without writing anything
+    // Minting to someone else needs the minter role, as in `sponsor_mint`
+    pub fn can_mint(minter: T::AccountId, to: T::AccountId, token_id: T::Hash) -> Result {
+        if minter != to {
+            ensure!(Self::is_minter(&minter), "You are not a minter");
+        }
+
+        Self::_check_user_mint(&minter, token_id)?;
+        Self::_check_mint_price(&minter)?;
+
+        ensure!(Self::balance_of(&to).checked_add(1).is_some(), "Overflow adding a new token to account balance");
+        ensure!(Self::creator_mint_count(&minter).checked_add(1).is_some(), "Overflow adding a new token to creator mint count");
+        ensure!(Self::total_supply().checked_add(1).is_some(), "Enumeration index overflow");
+
+        Ok(())
+    }
+
+    // Start ERC721 : Internal Functions //
+    fn _exists(token_id: T::Hash) -> bool {
+        return <TokenOwner<T>>::exists(token_id);
+    }
+
+    // `owner` is the token's current owner, read once by the caller
+    // Stops at the first check that approves, so the owner itself costs no read at all
+    fn _is_approved_or_owner(owner: &T::AccountId, spender: &T::AccountId, token_id: T::Hash) -> bool {
+        owner == spender
+            || Self::get_approved(token_id).as_ref() == Some(spender)
+            || Self::is_approved_for_all((owner.clone(), spender.clone()))
+            || (Self::is_default_operator(spender) && !Self::is_default_operator_revoked((owner.clone(), spender.clone())))
+    }
+
+    // The approval check of every call a spender signs. `Ok(true)` means the move spends the
+    // spender's single use approval, which `_spend_single_use` removes once the move succeeded
+    fn _check_spender(owner: &T::AccountId, spender: &T::AccountId, token_id: T::Hash) -> rstd::result::Result<bool, &'static str> {
+        if Self::single_use_approval(token_id).as_ref() == Some(spender) {
+            return Ok(true);
+        }
+
+        ensure!(Self::_is_approved_or_owner(owner, spender, token_id), "You do not own this token");
+
+        Ok(false)
+    }
+
+    // `_move_token` drops a single use approval too, but not for a transfer back to the owner
+    fn _spend_single_use(token_id: T::Hash, single_use: bool) {
+        if single_use {
+            <SingleUseApproval<T>>::remove(token_id);
+        }
+    }
+
+    // Everything that can make `_mint` fail for a fresh token, without writing anything
+    // Lets callers reject a mint before charging for it
+    fn _check_mint(creator: &T::AccountId, token_id: T::Hash) -> Result {
+        ensure!(!Self::_exists(token_id), "Token already exists");
+        Self::_check_mint_window()?;
+
+        if let Some(cap) = Self::per_creator_cap() {
+            ensure!(Self::creator_mint_count(creator) < cap, "Creator has reached the mint cap");
+        }
+
+        Ok(())
+    }
+
+    // Every mint path, the admin `mint` included, is closed outside the mint window
+    fn _check_mint_window() -> Result {
+        if let Some((start, end)) = Self::mint_window() {
+            let now = <system::Module<T>>::block_number();
+            ensure!(start <= now && now <= end, "Minting not open");
+        }
+
+        Ok(())
+    }
+
+    // `_check_mint` plus the restrictions that only apply to mints by regular accounts
+    fn _check_user_mint(creator: &T::AccountId, token_id: T::Hash) -> Result {
+        ensure!(!Self::is_reserved(token_id), "Token id is reserved");
+
+        Self::_check_mint(creator, token_id)
+    }
+
+    // Checked first, so a mint that cannot be paid fails with the error `can_mint` reports
+    fn _charge_mint_price(who: &T::AccountId) -> Result {
+        Self::_check_mint_price(who)?;
+
+        match Self::mint_price() {
+            Some(price) => <balances::Module<T>>::make_transfer(who, &Self::account_id(), price),
+            None => Ok(()),
+        }
+    }
+
+    // Fails wherever `_charge_mint_price` would, without writing anything
+    fn _check_mint_price(who: &T::AccountId) -> Result {
+        if let Some(price) = Self::mint_price() {
+            let liability = Self::_payment_liability(who, &Self::account_id(), price)?;
+            ensure!(<balances::Module<T>>::free_balance(who) >= liability, "Not enough free balance to pay the mint price");
+        }
+
+        Ok(())
     }
 
     fn _mint(to: T::AccountId, token_id: T::Hash) -> Result {
+        Self::_mint_with_creator(to.clone(), to, token_id)
+    }
+
+    // `_mint` for a token created by someone other than its first owner, e.g. a sponsor
+    // The creator is the one whose mint cap is used
+    fn _mint_with_creator(creator: T::AccountId, to: T::AccountId, token_id: T::Hash) -> Result {
+        Self::_check_mint(&creator, token_id)?;
+
+        Self::_mint_token(Some(creator), to, token_id)
+    }
+
+    // Writes a new token without the mint window and cap checks of `_check_mint`
+    // A token without a creator, e.g. from the admin `mint`, counts towards no one's cap
+    fn _mint_token(creator: Option<T::AccountId>, to: T::AccountId, token_id: T::Hash) -> Result {
         ensure!(!Self::_exists(token_id), "Token already exists");
 
         let balance_of = Self::balance_of(&to);
@@ -152,14 +1542,38 @@ impl<T: Trait> Module<T> {
             None => return Err("Overflow adding a new token to account balance"),
         };
 
+        let new_creator_mint_count = match creator {
+            Some(ref creator) => match Self::creator_mint_count(creator).checked_add(1) {
+                Some(c) => Some(c),
+                None => return Err("Overflow adding a new token to creator mint count"),
+            },
+            None => None,
+        };
+
+        // The enumeration helpers check this too, but only after other entries were written
+        ensure!(Self::total_supply().checked_add(1).is_some(), "Enumeration index overflow");
+
         // Writing to storage begins here
         Self::_add_token_to_all_tokens_enumeration(token_id)?;
-        Self::_add_token_to_owner_enumeration(to.clone(), token_id)?;
+        Self::_add_token_to_owner_enumeration(to.clone(), token_id, balance_of)?;
 
         <TokenOwner<T>>::insert(token_id, &to);
         <OwnedTokensCount<T>>::insert(&to, new_balance_of);
+        Self::_balance_changed(&to, balance_of, new_balance_of);
+        Self::_update_holder(&to, new_balance_of)?;
+        <OwnershipHistory<T>>::mutate(token_id, |history| history.push(to.clone()));
+        if let (Some(creator), Some(count)) = (creator, new_creator_mint_count) {
+            <CreatorMintCount<T>>::insert(&creator, count);
+            <TokenCreator<T>>::insert(token_id, &creator);
+        }
+        let now = <system::Module<T>>::block_number();
+        <MintedInBlock<T>>::mutate(&now, |tokens| tokens.push(token_id));
+        <MintBlock<T>>::insert(token_id, now);
+
+        <BlockMints<T>>::mutate(|count| *count = count.saturating_add(1));
 
-        Self::deposit_event(RawEvent::Transfer(None, Some(to), token_id));
+        Self::deposit_event(RawEvent::Transfer(None, Some(to.clone()), token_id));
+        Self::deposit_event(RawEvent::Minted(to, token_id));
 
         Ok(())
     }
@@ -170,6 +1584,9 @@ impl<T: Trait> Module<T> {
             None => return Err("No owner for this token"),
         };
 
+        // A lock is someone else's claim on the token, which a burn must not silently drop
+        ensure!(Self::token_locks(token_id).is_empty(), "Token is locked");
+
         let balance_of = Self::balance_of(&owner);
 
         let new_balance_of = match balance_of.checked_sub(1) {
@@ -179,15 +1596,110 @@ impl<T: Trait> Module<T> {
 
         // Writing to storage begins here
         Self::_remove_token_from_all_tokens_enumeration(token_id)?;
-        Self::_remove_token_from_owner_enumeration(owner.clone(), token_id)?;
+        Self::_remove_token_from_owner_enumeration(owner.clone(), token_id, balance_of)?;
         <OwnedTokensIndex<T>>::remove(token_id);
 
         Self::_clear_approval(token_id)?;
+        <SingleUseApproval<T>>::remove(token_id);
 
         <OwnedTokensCount<T>>::insert(&owner, new_balance_of);
+        Self::_balance_changed(&owner, balance_of, new_balance_of);
+        Self::_update_holder(&owner, new_balance_of)?;
         <TokenOwner<T>>::remove(token_id);
 
-        Self::deposit_event(RawEvent::Transfer(Some(owner), None, token_id));
+        // Every other per token entry goes with the token. `OwnershipHistory`, `MintedInBlock` and
+        // `MintBlock` are kept on purpose as permanent records, and so is a `ReservedTokens` reservation
+        <EscrowDepositor<T>>::remove(token_id);
+        Self::_delist(token_id);
+
+        for recipient in <WhitelistedRecipients<T>>::take(token_id) {
+            <TokenRecipientWhitelist<T>>::remove((token_id, recipient));
+        }
+
+        // Open offers can no longer be accepted, so their funds go back to the bidders
+        for bidder in <OfferBidders<T>>::take(token_id) {
+            let amount = <Offers<T>>::take((token_id, bidder.clone()));
+            <balances::Module<T>>::unreserve(&bidder, amount);
+        }
+        <HighestOffer<T>>::remove(token_id);
+
+        Self::_remove_from_bundle(token_id);
+
+        if let Some(creator) = <TokenCreator<T>>::take(token_id) {
+            if Self::burn_refunds_cap() {
+                <CreatorMintCount<T>>::mutate(&creator, |count| *count = count.saturating_sub(1));
+            }
+        }
+
+        <BlockBurns<T>>::mutate(|count| *count = count.saturating_add(1));
+
+        Self::deposit_event(RawEvent::Transfer(Some(owner.clone()), None, token_id));
+        Self::deposit_event(RawEvent::Burned(owner, token_id));
+        if <WrappedAsset<T>>::take(token_id).is_some() {
+            Self::deposit_event(RawEvent::Unwrapped(token_id));
+        }
+
+        Ok(())
+    }
+
+    // Storage changes are not rolled back when a call fails, so this undoes a `_mint`
+    // made earlier in the same call. Unlike `_burn` it also drops the mint from the
+    // creator's cap, the ownership history, the block's minted tokens and its stats
+    // Events are not rolled back either, so the mint's events stay and the burn's are added
+    fn _revert_mint(creator: &T::AccountId, token_id: T::Hash) -> Result {
+        Self::_burn(token_id)?;
+
+        <OwnershipHistory<T>>::remove(token_id);
+        <MintedInBlock<T>>::mutate(<system::Module<T>>::block_number(), |tokens| tokens.retain(|t| *t != token_id));
+        <MintBlock<T>>::remove(token_id);
+        <BlockMints<T>>::mutate(|count| *count = count.saturating_sub(1));
+        <BlockBurns<T>>::mutate(|count| *count = count.saturating_sub(1));
+        if !Self::burn_refunds_cap() {
+            <CreatorMintCount<T>>::mutate(creator, |count| *count = count.saturating_sub(1));
+        }
+
+        Ok(())
+    }
+
+    // Takes a token out of its bundle, and removes a bundle left without tokens
+    fn _remove_from_bundle(token_id: T::Hash) {
+        if let Some(bundle_id) = <TokenBundle<T>>::take(token_id) {
+            <Bundles<T>>::mutate(bundle_id, |tokens| tokens.retain(|t| *t != token_id));
+            if Self::bundle_tokens(bundle_id).is_empty() {
+                <Bundles<T>>::remove(bundle_id);
+            }
+        }
+    }
+
+    // The rules a transfer has to pass, without writing anything
+    // Lets callers reject a transfer before e.g. taking payment for it
+    // `owner` is the token's current owner, which the caller has already read
+    fn _check_transfer(owner: &T::AccountId, from: &T::AccountId, to: &T::AccountId, token_id: T::Hash) -> Result {
+        ensure!(Self::bundle_of(token_id).is_none(), "Token is bundled and can only move with its bundle");
+
+        Self::_check_transfer_rules(owner, from, to, token_id)
+    }
+
+    // `_check_transfer` for a token moving as part of its bundle
+    fn _check_transfer_rules(owner: &T::AccountId, from: &T::AccountId, to: &T::AccountId, token_id: T::Hash) -> Result {
+        Self::_check_sender_rules(owner, from, token_id)?;
+        T::TransferPolicy::can_transfer(from, to, &token_id)?;
+
+        ensure!(from != to || !Self::reject_self_transfer(), "Cannot transfer to current owner");
+
+        if !Self::whitelisted_recipients(token_id).is_empty() {
+            ensure!(Self::is_whitelisted_recipient((token_id, to.clone())), "Recipient is not whitelisted for this token");
+        }
+
+        Ok(())
+    }
+
+    // The part of `_check_transfer_rules` that does not depend on the recipient
+    fn _check_sender_rules(owner: &T::AccountId, from: &T::AccountId, token_id: T::Hash) -> Result {
+        ensure!(Self::token_locks(token_id).is_empty(), "Token is locked");
+        ensure!(owner == from, "'from' account does not own this token");
+        // The module account cannot sign an approval, so `reclaim` moves its tokens without one
+        ensure!(!Self::strict_approval() || from == &Self::account_id() || Self::get_approved(token_id).is_some(), "Token has no active approval");
 
         Ok(())
     }
@@ -197,8 +1709,22 @@ impl<T: Trait> Module<T> {
             Some(c) => c,
             None => return Err("No owner for this token"),
         };
+        Self::_check_transfer(&owner, &from, &to, token_id)?;
 
-        ensure!(owner == from, "'from' account does not own this token");
+        Self::_move_token(from, to, token_id)
+    }
+
+    // The writes of `_transfer_from`, for callers that already ran the transfer checks
+    fn _move_token(from: T::AccountId, to: T::AccountId, token_id: T::Hash) -> Result {
+        // The balance math below assumes two different accounts, so a move to the owner only
+        // clears the approval like any other transfer would
+        if from == to {
+            if Self::clear_approval_on_transfer() {
+                Self::_clear_approval(token_id)?;
+            }
+            Self::deposit_event(RawEvent::Transfer(Some(from), Some(to), token_id));
+            return Ok(());
+        }
 
         let balance_of_from = Self::balance_of(&from);
         let balance_of_to = Self::balance_of(&to);
@@ -214,13 +1740,30 @@ impl<T: Trait> Module<T> {
         };
 
         // Writing to storage begins here
-        Self::_remove_token_from_owner_enumeration(from.clone(), token_id)?;
-        Self::_add_token_to_owner_enumeration(to.clone(), token_id)?;
-        
-        Self::_clear_approval(token_id)?;
+        Self::_remove_token_from_owner_enumeration(from.clone(), token_id, balance_of_from)?;
+        Self::_add_token_to_owner_enumeration(to.clone(), token_id, balance_of_to)?;
+
+        if Self::clear_approval_on_transfer() {
+            Self::_clear_approval(token_id)?;
+        }
+        // A single use approval was granted by the old owner, so it never survives a transfer
+        <SingleUseApproval<T>>::remove(token_id);
         <OwnedTokensCount<T>>::insert(&from, new_balance_of_from);
         <OwnedTokensCount<T>>::insert(&to, new_balance_of_to);
+        Self::_balance_changed(&from, balance_of_from, new_balance_of_from);
+        Self::_balance_changed(&to, balance_of_to, new_balance_of_to);
+        Self::_update_holder(&from, new_balance_of_from)?;
+        Self::_update_holder(&to, new_balance_of_to)?;
         <TokenOwner<T>>::insert(&token_id, &to);
+        <OwnershipHistory<T>>::mutate(token_id, |history| history.push(to.clone()));
+        <SentTransfers<T>>::mutate(&from, |tokens| tokens.push(token_id));
+        <ReceivedTransfers<T>>::mutate(&to, |tokens| tokens.push(token_id));
+        if to != Self::account_id() {
+            <EscrowDepositor<T>>::remove(token_id);
+        }
+        Self::_delist(token_id);
+
+        <BlockTransfers<T>>::mutate(|count| *count = count.saturating_add(1));
 
         Self::deposit_event(RawEvent::Transfer(Some(from), Some(to), token_id));
         
@@ -235,8 +1778,10 @@ impl<T: Trait> Module<T> {
     // End ERC721 : Internal Functions //
 
     // Start ERC721 : Enumerable : Internal Functions //
-    fn _add_token_to_owner_enumeration(to: T::AccountId, token_id: T::Hash) -> Result {
-        let new_token_index = Self::balance_of(&to);
+    // `balance_of_to` is the owner's balance before the token is added, which callers already read
+    fn _add_token_to_owner_enumeration(to: T::AccountId, token_id: T::Hash, balance_of_to: u64) -> Result {
+        let new_token_index = balance_of_to;
+        ensure!(new_token_index.checked_add(1).is_some(), "Enumeration index overflow");
 
         <OwnedTokensIndex<T>>::insert(token_id, new_token_index);
         <OwnedTokens<T>>::insert((to, new_token_index), token_id);
@@ -262,9 +1807,8 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
-    fn _remove_token_from_owner_enumeration(from: T::AccountId, token_id: T::Hash) -> Result {
-        let balance_of_from = Self::balance_of(&from);
-
+    // `balance_of_from` is the owner's balance before the token is removed, which callers already read
+    fn _remove_token_from_owner_enumeration(from: T::AccountId, token_id: T::Hash, balance_of_from: u64) -> Result {
         // Should never fail because same check happens before this call is made
         let last_token_index = match balance_of_from.checked_sub(1) {
             Some (c) => c,
@@ -273,7 +1817,14 @@ impl<T: Trait> Module<T> {
         
         let token_index = <OwnedTokensIndex<T>>::get(token_id);
 
-        if token_index != last_token_index {
+        if Self::stable_ordering() {
+            // Shift every later token down by one, keeping insertion order
+            for index in token_index..last_token_index {
+                let next_token_id = <OwnedTokens<T>>::get((from.clone(), index + 1));
+                <OwnedTokens<T>>::insert((from.clone(), index), next_token_id);
+                <OwnedTokensIndex<T>>::insert(next_token_id, index);
+            }
+        } else if token_index != last_token_index {
             let last_token_id = <OwnedTokens<T>>::get((from.clone(), last_token_index));
             <OwnedTokens<T>>::insert((from.clone(), token_index), last_token_id);
             <OwnedTokensIndex<T>>::insert(last_token_id, token_index);
@@ -312,4 +1863,130 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
     // End ERC721 : Enumerable : Internal Functions //
+
+    fn _marketplace_fee(price: T::Balance) -> rstd::result::Result<T::Balance, &'static str> {
+        let fee_bps = <T::Balance as As<u64>>::sa(Self::marketplace_fee_bps().min(10_000) as u64);
+
+        match price.checked_mul(&fee_bps) {
+            Some(c) => Ok(c / <T::Balance as As<u64>>::sa(10_000)),
+            None => Err("Overflow computing the marketplace fee"),
+        }
+    }
+
+    // Not part of ERC721, drops an offer and finds the next highest one if it was the highest
+    // Scans the token's bidders, which `MAX_OFFERS_PER_TOKEN` keeps short
+    fn _remove_offer(token_id: T::Hash, buyer: &T::AccountId) {
+        <Offers<T>>::remove((token_id, buyer.clone()));
+        <OfferBidders<T>>::mutate(token_id, |bidders| bidders.retain(|b| b != buyer));
+
+        let was_highest = match Self::highest_offer(token_id) {
+            Some((ref highest_bidder, _)) => highest_bidder == buyer,
+            None => false,
+        };
+        if !was_highest {
+            return;
+        }
+
+        let mut highest: Option<(T::AccountId, T::Balance)> = None;
+        for bidder in Self::offer_bidders(token_id) {
+            let amount = Self::offer_of((token_id, bidder.clone()));
+            let is_higher = match highest {
+                Some((_, ref h)) => amount > *h,
+                None => true,
+            };
+            if is_higher {
+                highest = Some((bidder, amount));
+            }
+        }
+
+        match highest {
+            Some(offer) => <HighestOffer<T>>::insert(token_id, offer),
+            None => <HighestOffer<T>>::remove(token_id),
+        }
+    }
+
+    // Not part of ERC721, drops a sale listing if there is one
+    fn _delist(token_id: T::Hash) {
+        if <SalePrice<T>>::take(token_id).is_some() {
+            Self::deposit_event(RawEvent::Delisted(token_id));
+        }
+    }
+
+    fn _balance_changed(who: &T::AccountId, old_balance: u64, new_balance: u64) {
+        if Self::emit_balance_events() {
+            Self::deposit_event(RawEvent::BalanceChanged(who.clone(), old_balance, new_balance));
+        }
+    }
+
+    // Not part of ERC721, keeps `Holders` in step with an account's new token balance
+    fn _update_holder(who: &T::AccountId, new_balance: u64) -> Result {
+        let is_holder = Self::is_holder(who);
+
+        if new_balance > 0 && !is_holder {
+            let holder_count = Self::holder_count();
+            let new_holder_count = match holder_count.checked_add(1) {
+                Some(c) => c,
+                None => return Err("Overflow adding a new holder"),
+            };
+
+            <AllHolders<T>>::insert(holder_count, who);
+            <AllHoldersIndex<T>>::insert(who, holder_count);
+            <HolderCount<T>>::put(new_holder_count);
+            <Holders<T>>::insert(who, true);
+        } else if new_balance == 0 && is_holder {
+            let last_holder_index = match Self::holder_count().checked_sub(1) {
+                Some(c) => c,
+                None => return Err("Underflow removing a holder"),
+            };
+
+            let holder_index = <AllHoldersIndex<T>>::get(who);
+            if holder_index != last_holder_index {
+                let last_holder = <AllHolders<T>>::get(last_holder_index);
+                <AllHolders<T>>::insert(holder_index, &last_holder);
+                <AllHoldersIndex<T>>::insert(&last_holder, holder_index);
+            }
+
+            <AllHolders<T>>::remove(last_holder_index);
+            <AllHoldersIndex<T>>::remove(who);
+            <HolderCount<T>>::put(last_holder_index);
+            <Holders<T>>::remove(who);
+        }
+
+        Ok(())
+    }
+
+    // Not part of ERC721, what `balances::make_transfer` takes from `payer` to pay `value` to `to`,
+    // failing wherever it would fail for a reason other than the payer's balance. Lets calls check
+    // every payment up front, as storage is not rolled back when a later payment fails
+    fn _payment_liability(payer: &T::AccountId, to: &T::AccountId, value: T::Balance) -> rstd::result::Result<T::Balance, &'static str> {
+        <T::EnsureAccountLiquid as balances::EnsureAccountLiquid<T::AccountId>>::ensure_account_liquid(payer)?;
+
+        let to_balance = <balances::Module<T>>::free_balance(to);
+        let would_create = to_balance.is_zero();
+        ensure!(!would_create || value >= <balances::Module<T>>::existential_deposit(), "value too low to create account");
+        ensure!(to_balance.checked_add(&value).is_some(), "destination balance too high to receive value");
+
+        let fee = if would_create {
+            <balances::Module<T>>::creation_fee()
+        } else {
+            <balances::Module<T>>::transfer_fee()
+        };
+
+        match value.checked_add(&fee) {
+            Some(c) => Ok(c),
+            None => Err("got overflow after adding a fee to value"),
+        }
+    }
+
+    // Not part of ERC721, checks that `signer` signed `payload` off-chain
+    fn _verify_signature(signer: &T::AccountId, payload: &[u8], signature: Vec<u8>) -> Result {
+        let signature = match <T::Signature as Decode>::decode(&mut &signature[..]) {
+            Some(s) => s,
+            None => return Err("Signature could not be decoded"),
+        };
+
+        ensure!(signature.verify(payload, signer), "Invalid signature");
+
+        Ok(())
+    }
 }
@@ -184,6 +184,14 @@ impl sudo::Trait for Runtime {
 
 impl erc721::Trait for Runtime {
 	type Event = Event;
+	/// No restrictions on transfers beyond the ERC721 rules.
+	type TransferPolicy = ();
+	/// No marketplace module to hand listings to.
+	type MarketplaceHook = ();
+	/// Token ids use the same hash as the rest of the chain.
+	type TokenIdHasher = BlakeTwo256;
+	/// Off-chain signatures are made with the same keys as extrinsics.
+	type Signature = Ed25519Signature;
 }
 
 construct_runtime!(
@@ -198,7 +206,7 @@ construct_runtime!(
 		Aura: aura::{Module},
 		Balances: balances,
 		Sudo: sudo,
-		Erc721: erc721::{Module, Call, Storage, Event<T>},
+		Erc721: erc721::{Module, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -219,6 +227,14 @@ pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Nonce, Call>;
 /// Executive: handles dispatch to the various modules.
 pub type Executive = executive::Executive<Runtime, Block, Context, Balances, AllModules>;
 
+decl_runtime_apis! {
+	/// Reads of the ERC721 module for clients that do not keep full state.
+	pub trait Erc721Api {
+		/// The owner of `token_id` and the storage key holding it, to request a proof for.
+		fn owner_proof(token_id: Hash) -> Option<(AccountId, Vec<u8>)>;
+	}
+}
+
 // Implement our runtime API endpoints. This is just a bunch of proxying.
 impl_runtime_apis! {
 	impl runtime_api::Core<Block> for Runtime {
@@ -293,4 +309,10 @@ impl_runtime_apis! {
 			Aura::slot_duration()
 		}
 	}
+
+	impl self::Erc721Api<Block> for Runtime {
+		fn owner_proof(token_id: Hash) -> Option<(AccountId, Vec<u8>)> {
+			Erc721::owner_proof(token_id)
+		}
+	}
 }
@@ -0,0 +1,161 @@
+// Test runtime for the ERC721 module
+// Accounts are `H256` like in the real runtime, so off-chain signatures can be made with real keys
+
+use super::*;
+use std::cell::RefCell;
+use primitives::{H256, Blake2Hasher, ed25519};
+use runtime_primitives::{BuildStorage, Ed25519Signature};
+use runtime_primitives::traits::BlakeTwo256;
+use runtime_primitives::testing::{Digest, DigestItem, Header};
+use erc721;
+
+impl_outer_origin! {
+    pub enum Origin for Test {}
+}
+
+impl_outer_event! {
+    pub enum TestEvent for Test {
+        balances<T>, erc721<T>,
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Test;
+
+impl system::Trait for Test {
+    type Origin = Origin;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type Digest = Digest;
+    type AccountId = H256;
+    type Header = Header;
+    type Event = TestEvent;
+    type Log = DigestItem;
+}
+
+impl balances::Trait for Test {
+    type Balance = u64;
+    type AccountIndex = u64;
+    type OnFreeBalanceZero = ();
+    type EnsureAccountLiquid = ();
+    type Event = TestEvent;
+}
+
+impl Trait for Test {
+    type Event = TestEvent;
+    type TransferPolicy = MockTransferPolicy;
+    type MarketplaceHook = MockMarketplace;
+    type TokenIdHasher = BlakeTwo256;
+    type Signature = Ed25519Signature;
+}
+
+pub type System = system::Module<Test>;
+pub type Balances = balances::Module<Test>;
+pub type Erc721 = Module<Test>;
+
+thread_local! {
+    static REFUSE_LISTINGS: RefCell<bool> = RefCell::new(false);
+    static BLOCKED_RECIPIENT: RefCell<Option<H256>> = RefCell::new(None);
+}
+
+// Transfer policy that allows every transfer except those to the account a test blocked
+pub struct MockTransferPolicy;
+
+impl MockTransferPolicy {
+    pub fn block_recipient(who: Option<H256>) {
+        BLOCKED_RECIPIENT.with(|b| *b.borrow_mut() = who);
+    }
+}
+
+impl TransferPolicy<H256, H256> for MockTransferPolicy {
+    fn can_transfer(_: &H256, to: &H256, _: &H256) -> Result {
+        if BLOCKED_RECIPIENT.with(|b| *b.borrow() == Some(*to)) {
+            return Err("Transfer policy rejected the recipient");
+        }
+
+        Ok(())
+    }
+}
+
+// Marketplace that accepts every listing until a test tells it to refuse them
+pub struct MockMarketplace;
+
+impl MockMarketplace {
+    pub fn refuse_listings(refuse: bool) {
+        REFUSE_LISTINGS.with(|r| *r.borrow_mut() = refuse);
+    }
+}
+
+impl MarketplaceHook<H256, H256, u64> for MockMarketplace {
+    fn list(_: &H256, _: &H256, _: u64) -> Result {
+        if REFUSE_LISTINGS.with(|r| *r.borrow()) {
+            return Err("Marketplace refused the listing");
+        }
+
+        Ok(())
+    }
+}
+
+// Accounts 1 to 4 start with `ENDOWMENT` each, every other account starts empty
+pub const ENDOWMENT: u64 = 1_000;
+
+pub fn account(n: u64) -> H256 {
+    H256::from_low_u64_be(n)
+}
+
+// A key pair for tests that need an account to sign a payload off-chain
+pub fn key(seed: u8) -> ed25519::Pair {
+    ed25519::Pair::from_seed(&[seed; 32])
+}
+
+pub fn key_account(seed: u8) -> H256 {
+    H256::from(key(seed).public().0)
+}
+
+// The encoding `_verify_signature` decodes into `Ed25519Signature`
+pub fn sign(seed: u8, payload: &[u8]) -> Vec<u8> {
+    Ed25519Signature(key(seed).sign(payload).0.into()).encode()
+}
+
+pub fn token(n: u64) -> H256 {
+    H256::from_low_u64_be(0x7000 + n)
+}
+
+pub fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+    new_test_ext_with_fees(0, 0, 0)
+}
+
+// Balances charge `transfer_fee` on payments to existing accounts and `creation_fee` on
+// payments that create one, which must also be at least `existential_deposit`
+pub fn new_test_ext_with_fees(existential_deposit: u64, transfer_fee: u64, creation_fee: u64) -> runtime_io::TestExternalities<Blake2Hasher> {
+    MockMarketplace::refuse_listings(false);
+    MockTransferPolicy::block_recipient(None);
+
+    let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap();
+    t.extend(balances::GenesisConfig::<Test> {
+        balances: (1..5).map(|n| (account(n), ENDOWMENT)).collect(),
+        transaction_base_fee: 0,
+        transaction_byte_fee: 0,
+        existential_deposit,
+        transfer_fee,
+        creation_fee,
+        reclaim_rebate: 0,
+    }.build_storage().unwrap());
+    t.extend(GenesisConfig::<Test>::default().build_storage().unwrap());
+    t.into()
+}
+
+// Erc721 events deposited so far, oldest first
+pub fn erc721_events() -> Vec<RawEvent<H256, H256, u64>> {
+    System::events().into_iter().filter_map(|record| match record.event {
+        TestEvent::erc721(e) => Some(e),
+        _ => None,
+    }).collect()
+}
+
+// The most recent Erc721 event, for tests that only care about the outcome of the last call
+pub fn last_event() -> Option<RawEvent<H256, H256, u64>> {
+    erc721_events().pop()
+}
@@ -0,0 +1,2068 @@
+// Tests of the ERC721 module against the test runtime in `mock.rs`
+
+use super::*;
+use super::mock::*;
+use primitives::H256;
+use runtime_io::with_externalities;
+
+// Creates a token for `owner` through `create_token` and returns its id
+fn create_token_for(owner: H256) -> H256 {
+    assert_ok!(Erc721::create_token(Origin::signed(owner)));
+    Erc721::token_of_owner_by_index((owner, Erc721::balance_of(&owner) - 1))
+}
+
+#[test]
+fn create_token_mints_to_the_sender() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(1)));
+        assert_eq!(Erc721::balance_of(&account(1)), 1);
+        assert_eq!(Erc721::total_supply(), 1);
+        assert_eq!(Erc721::token_by_index(0), token_id);
+        assert_eq!(Erc721::token_of_owner_by_index((account(1), 0)), token_id);
+        assert_eq!(erc721_events(), vec![
+            RawEvent::Transfer(None, Some(account(1)), token_id),
+            RawEvent::Minted(account(1), token_id),
+        ]);
+    });
+}
+
+#[test]
+fn create_token_gives_every_token_a_new_id() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+        let third = create_token_for(account(2));
+
+        assert!(first != second && second != third && first != third);
+        assert_eq!(Erc721::balance_of(&account(1)), 2);
+        assert_eq!(Erc721::balance_of(&account(2)), 1);
+        assert_eq!(Erc721::total_supply(), 3);
+    });
+}
+
+#[test]
+fn approve_sets_the_approved_account() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(2), token_id));
+
+        assert_eq!(Erc721::get_approved(token_id), Some(account(2)));
+        assert_eq!(last_event(), Some(RawEvent::Approval(account(1), account(2), token_id)));
+    });
+}
+
+#[test]
+fn approve_can_be_made_by_an_operator() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(2), true));
+
+        assert_ok!(Erc721::approve(Origin::signed(account(2)), account(3), token_id));
+
+        assert_eq!(Erc721::get_approved(token_id), Some(account(3)));
+    });
+}
+
+#[test]
+fn approve_rejects_accounts_that_may_not_approve() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(Erc721::approve(Origin::signed(account(2)), account(3), token_id), "You are not allowed to approve for this token");
+        assert_noop!(Erc721::approve(Origin::signed(account(1)), account(1), token_id), "Owner is implicitly approved");
+        assert_noop!(Erc721::approve(Origin::signed(account(1)), account(2), token(99)), "No owner for this token");
+    });
+}
+
+#[test]
+fn set_approval_for_all_approves_and_revokes_an_operator() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(2), true));
+        assert!(Erc721::is_approved_for_all((account(1), account(2))));
+        assert_eq!(Erc721::approved_operators(&account(1)), vec![account(2)]);
+
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(2), false));
+        assert!(!Erc721::is_approved_for_all((account(1), account(2))));
+        assert!(Erc721::approved_operators(&account(1)).is_empty());
+
+        assert_eq!(erc721_events(), vec![
+            RawEvent::ApprovalForAll(account(1), account(2), true),
+            RawEvent::ApprovalForAll(account(1), account(2), false),
+        ]);
+    });
+}
+
+#[test]
+fn set_approval_for_all_rejects_the_sender_as_operator() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_noop!(
+            Erc721::set_approval_for_all(Origin::signed(account(1)), account(1), true),
+            "You are already implicity approved for your own actions"
+        );
+    });
+}
+
+#[test]
+fn transfer_from_moves_the_token_and_clears_its_approval() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(3), token_id));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+        assert_eq!(Erc721::balance_of(&account(1)), 0);
+        assert_eq!(Erc721::balance_of(&account(2)), 1);
+        assert_eq!(Erc721::token_of_owner_by_index((account(2), 0)), token_id);
+        assert_eq!(Erc721::get_approved(token_id), None);
+        assert_eq!(last_event(), Some(RawEvent::Transfer(Some(account(1)), Some(account(2)), token_id)));
+    });
+}
+
+#[test]
+fn transfer_from_works_for_approved_accounts_and_operators() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(2), token_id));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(2)), account(1), account(3), token_id));
+        assert_eq!(Erc721::owner_of(token_id), Some(account(3)));
+
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(3)), account(4), true));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(4)), account(3), account(1), token_id));
+        assert_eq!(Erc721::owner_of(token_id), Some(account(1)));
+    });
+}
+
+#[test]
+fn transfer_from_rejects_invalid_transfers() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(Erc721::transfer_from(Origin::signed(account(2)), account(1), account(2), token_id), "You do not own this token");
+        assert_noop!(Erc721::transfer_from(Origin::signed(account(1)), account(2), account(3), token_id), "'from' account does not own this token");
+        assert_noop!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token(99)), "No owner for this token");
+    });
+}
+
+#[test]
+fn safe_transfer_from_rejects_recipients_without_a_balance() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_eq!(Balances::free_balance(&account(9)), 0);
+
+        assert_noop!(
+            Erc721::safe_transfer_from(Origin::signed(account(1)), account(1), account(9), token_id),
+            "'to' account does not satisfy the `ExistentialDeposit` requirement"
+        );
+        assert_eq!(Erc721::owner_of(token_id), Some(account(1)));
+    });
+}
+
+#[test]
+fn safe_transfer_from_moves_the_token_to_recipients_with_a_balance() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert!(Balances::free_balance(&account(2)) > 0);
+
+        assert_ok!(Erc721::safe_transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+    });
+}
+
+#[test]
+fn mint_and_list_charges_the_mint_price_once_listed() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::set_mint_price(Origin::ROOT, Some(100)));
+
+        assert_ok!(Erc721::mint_and_list(Origin::signed(account(1)), token(1), 500));
+
+        assert_eq!(Erc721::owner_of(token(1)), Some(account(1)));
+        assert_eq!(Balances::free_balance(&account(1)), ENDOWMENT - 100);
+        assert_eq!(Balances::free_balance(&Erc721::account_id()), 100);
+    });
+}
+
+#[test]
+fn mint_and_list_refused_by_the_marketplace_charges_nothing() {
+    // The price leaves the minter under the existential deposit, so a refund after charging
+    // would have had to recreate the minter's account
+    with_externalities(&mut new_test_ext_with_fees(10, 0, 1), || {
+        assert_ok!(Erc721::set_mint_price(Origin::ROOT, Some(ENDOWMENT - 5)));
+        MockMarketplace::refuse_listings(true);
+
+        assert_err!(Erc721::mint_and_list(Origin::signed(account(1)), token(1), 500), "Marketplace refused the listing");
+
+        assert_eq!(Erc721::owner_of(token(1)), None);
+        assert_eq!(Erc721::total_supply(), 0);
+        assert_eq!(Erc721::creator_mint_count(&account(1)), 0);
+        assert_eq!(Balances::free_balance(&account(1)), ENDOWMENT);
+        assert_eq!(Balances::free_balance(&Erc721::account_id()), 0);
+    });
+}
+
+#[test]
+fn mint_and_list_refused_by_the_marketplace_leaves_the_mint_and_burn_events() {
+    with_externalities(&mut new_test_ext(), || {
+        <EmitBalanceEvents<Test>>::put(true);
+        MockMarketplace::refuse_listings(true);
+
+        assert_err!(Erc721::mint_and_list(Origin::signed(account(1)), token(1), 500), "Marketplace refused the listing");
+
+        assert_eq!(erc721_events(), vec![
+            RawEvent::BalanceChanged(account(1), 0, 1),
+            RawEvent::Transfer(None, Some(account(1)), token(1)),
+            RawEvent::Minted(account(1), token(1)),
+            RawEvent::BalanceChanged(account(1), 1, 0),
+            RawEvent::Transfer(Some(account(1)), None, token(1)),
+            RawEvent::Burned(account(1), token(1)),
+        ]);
+    });
+}
+
+#[test]
+fn mint_and_list_rejects_minters_who_cannot_pay_before_minting() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::set_mint_price(Origin::ROOT, Some(ENDOWMENT + 1)));
+
+        assert_noop!(Erc721::mint_and_list(Origin::signed(account(1)), token(1), 500), "Not enough free balance to pay the mint price");
+    });
+}
+
+#[test]
+fn mint_by_root_ignores_the_cap_and_reserved_ids() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::set_per_creator_cap(Origin::ROOT, Some(0)));
+        assert_ok!(Erc721::reserve_token_id(Origin::ROOT, token(1)));
+        assert_noop!(Erc721::create_token(Origin::signed(account(1))), "Creator has reached the mint cap");
+
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+
+        assert_eq!(Erc721::owner_of(token(1)), Some(account(1)));
+        assert_eq!(Erc721::creator_of(token(1)), None);
+        assert_eq!(Erc721::creator_mint_count(&account(1)), 0);
+    });
+}
+
+#[test]
+fn mint_by_root_rejects_existing_tokens_and_signed_origins() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+
+        assert_noop!(Erc721::mint(Origin::ROOT, account(2), token(1)), "Token already exists");
+        assert_noop!(Erc721::mint(Origin::signed(account(1)), account(1), token(2)), "bad origin: expected to be a root origin");
+    });
+}
+
+// Lists a new token of `owner` for `price` with a marketplace fee of `fee_bps`
+fn list_token_for(owner: H256, price: u64, fee_bps: u32) -> H256 {
+    <MarketplaceFeeBps<Test>>::put(fee_bps);
+    let token_id = create_token_for(owner);
+    assert_ok!(Erc721::list_for_sale(Origin::signed(owner), token_id, price));
+    token_id
+}
+
+#[test]
+fn buy_pays_the_fee_and_the_seller() {
+    with_externalities(&mut new_test_ext_with_fees(10, 0, 0), || {
+        let token_id = list_token_for(account(1), 500, 1_000);
+
+        assert_ok!(Erc721::buy(Origin::signed(account(2)), token_id, 500));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+        assert_eq!(Balances::free_balance(&account(2)), ENDOWMENT - 500);
+        assert_eq!(Balances::free_balance(&account(1)), ENDOWMENT + 450);
+        assert_eq!(Balances::free_balance(&Erc721::account_id()), 50);
+    });
+}
+
+#[test]
+fn buy_checks_the_transfer_fees_before_paying() {
+    with_externalities(&mut new_test_ext_with_fees(10, 5, 5), || {
+        // The fee creates the module account and the payout goes to an existing one,
+        // so the buyer needs 10 more than the price
+        let token_id = list_token_for(account(1), ENDOWMENT, 1_000);
+
+        assert_noop!(Erc721::buy(Origin::signed(account(2)), token_id, ENDOWMENT), "Not enough free balance to buy this token");
+    });
+}
+
+#[test]
+fn buy_rejects_a_fee_too_low_to_create_the_module_account() {
+    with_externalities(&mut new_test_ext_with_fees(10, 0, 0), || {
+        let token_id = list_token_for(account(1), 50, 1_000);
+
+        assert_noop!(Erc721::buy(Origin::signed(account(2)), token_id, 50), "value too low to create account");
+    });
+}
+
+#[test]
+fn buy_rejects_buyers_the_fee_would_leave_below_the_existential_deposit() {
+    with_externalities(&mut new_test_ext_with_fees(100, 0, 0), || {
+        Balances::set_free_balance(&account(5), 520);
+        let token_id = list_token_for(account(1), 500, 9_000);
+
+        assert_noop!(Erc721::buy(Origin::signed(account(5)), token_id, 500), "Not enough free balance to buy this token");
+    });
+}
+
+#[test]
+fn buy_rejects_a_price_raised_above_the_buyers_maximum() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = list_token_for(account(1), 500, 0);
+        assert_ok!(Erc721::update_price(Origin::signed(account(1)), token_id, 900));
+
+        assert_noop!(Erc721::buy(Origin::signed(account(2)), token_id, 500), "Price is above your maximum");
+
+        assert_ok!(Erc721::buy(Origin::signed(account(2)), token_id, 900));
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+    });
+}
+
+#[test]
+fn a_listing_can_be_repriced_and_is_dropped_by_a_transfer() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = list_token_for(account(1), 500, 0);
+        assert_ok!(Erc721::update_price(Origin::signed(account(1)), token_id, 600));
+        assert_eq!(Erc721::sale_price(token_id), Some(600));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+
+        assert_eq!(Erc721::sale_price(token_id), None);
+        let events = erc721_events();
+        assert!(events.contains(&RawEvent::Listed(token_id, 500)));
+        assert!(events.contains(&RawEvent::PriceChanged(token_id, 500, 600)));
+        assert!(events.contains(&RawEvent::Delisted(token_id)));
+        assert_noop!(Erc721::buy(Origin::signed(account(3)), token_id, 600), "Token is not for sale");
+    });
+}
+
+#[test]
+fn delist_is_only_for_the_owner_of_a_listed_token() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = list_token_for(account(1), 500, 0);
+
+        assert_noop!(Erc721::delist(Origin::signed(account(2)), token_id), "You do not own this token");
+        assert_ok!(Erc721::delist(Origin::signed(account(1)), token_id));
+
+        assert_eq!(Erc721::sale_price(token_id), None);
+        assert_eq!(Erc721::get_approved(token_id), None);
+        assert_eq!(last_event(), Some(RawEvent::Delisted(token_id)));
+        assert_noop!(Erc721::delist(Origin::signed(account(1)), token_id), "Token is not for sale");
+    });
+}
+
+#[test]
+fn accept_offer_pays_the_owner_from_the_reserve() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::make_offer(Origin::signed(account(2)), token_id, 300));
+        assert_eq!(Balances::reserved_balance(&account(2)), 300);
+
+        assert_ok!(Erc721::accept_offer(Origin::signed(account(1)), token_id, account(2)));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+        assert_eq!(Balances::reserved_balance(&account(2)), 0);
+        assert_eq!(Balances::free_balance(&account(2)), ENDOWMENT - 300);
+        assert_eq!(Balances::free_balance(&account(1)), ENDOWMENT + 300);
+        assert_eq!(Erc721::offer_of((token_id, account(2))), 0);
+        assert_eq!(Erc721::highest_offer(token_id), None);
+    });
+}
+
+#[test]
+fn accept_offer_rejects_an_offer_no_longer_fully_reserved() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::make_offer(Origin::signed(account(2)), token_id, 300));
+        Balances::unreserve(&account(2), 100);
+
+        assert_noop!(Erc721::accept_offer(Origin::signed(account(1)), token_id, account(2)), "Offer is no longer fully reserved");
+        assert_eq!(Erc721::owner_of(token_id), Some(account(1)));
+    });
+}
+
+#[test]
+fn withdraw_offer_unreserves_and_the_next_highest_offer_takes_over() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::make_offer(Origin::signed(account(2)), token_id, 300));
+        assert_ok!(Erc721::make_offer(Origin::signed(account(3)), token_id, 500));
+        assert_eq!(Erc721::highest_offer(token_id), Some((account(3), 500)));
+
+        assert_ok!(Erc721::withdraw_offer(Origin::signed(account(3)), token_id));
+
+        assert_eq!(Balances::reserved_balance(&account(3)), 0);
+        assert_eq!(Balances::free_balance(&account(3)), ENDOWMENT);
+        assert_eq!(Erc721::highest_offer(token_id), Some((account(2), 300)));
+    });
+}
+
+#[test]
+fn burn_for_refund_pays_the_owner_from_the_pool() {
+    with_externalities(&mut new_test_ext_with_fees(10, 5, 5), || {
+        <RefundAmount<Test>>::put(100);
+        Balances::set_free_balance(&Erc721::account_id(), 500);
+        let token_id = create_token_for(account(1));
+
+        assert_ok!(Erc721::burn_for_refund(Origin::signed(account(1)), token_id));
+
+        assert_eq!(Erc721::owner_of(token_id), None);
+        assert_eq!(Balances::free_balance(&account(1)), ENDOWMENT + 100);
+        assert_eq!(Balances::free_balance(&Erc721::account_id()), 395);
+    });
+}
+
+#[test]
+fn burn_for_refund_keeps_the_token_when_the_pool_cannot_pay() {
+    with_externalities(&mut new_test_ext_with_fees(10, 5, 5), || {
+        <RefundAmount<Test>>::put(100);
+        let token_id = create_token_for(account(1));
+
+        // Empty pool
+        assert_noop!(Erc721::burn_for_refund(Origin::signed(account(1)), token_id), "Refund pool is depleted");
+
+        // Enough for the refund, but not for its fee
+        Balances::set_free_balance(&Erc721::account_id(), 100);
+        assert_noop!(Erc721::burn_for_refund(Origin::signed(account(1)), token_id), "Refund pool is depleted");
+
+        // Enough for the refund and its fee, but not for the pool's existential deposit
+        Balances::set_free_balance(&Erc721::account_id(), 110);
+        assert_noop!(Erc721::burn_for_refund(Origin::signed(account(1)), token_id), "Refund pool is depleted");
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(1)));
+    });
+}
+
+#[test]
+fn burn_for_refund_rejects_a_refund_too_low_to_create_the_owners_account() {
+    with_externalities(&mut new_test_ext_with_fees(10, 0, 0), || {
+        <RefundAmount<Test>>::put(5);
+        Balances::set_free_balance(&Erc721::account_id(), 500);
+        assert_ok!(Erc721::mint(Origin::ROOT, account(9), token(1)));
+
+        assert_noop!(Erc721::burn_for_refund(Origin::signed(account(9)), token(1)), "value too low to create account");
+        assert_eq!(Erc721::owner_of(token(1)), Some(account(9)));
+    });
+}
+
+#[test]
+fn a_bundle_moves_as_a_whole_and_not_token_by_token() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+        assert_ok!(Erc721::create_bundle(Origin::signed(account(1)), vec![first, second]));
+        let bundle_id = Erc721::bundle_of(first).unwrap();
+
+        assert_noop!(
+            Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), first),
+            "Token is bundled and can only move with its bundle"
+        );
+
+        assert_ok!(Erc721::transfer_bundle(Origin::signed(account(1)), bundle_id, account(2)));
+        assert_eq!(Erc721::owner_of(first), Some(account(2)));
+        assert_eq!(Erc721::owner_of(second), Some(account(2)));
+        assert_eq!(Erc721::bundle_tokens(bundle_id), vec![first, second]);
+    });
+}
+
+#[test]
+fn transfer_bundle_rejects_the_module_account() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::create_bundle(Origin::signed(account(1)), vec![token_id]));
+        let bundle_id = Erc721::bundle_of(token_id).unwrap();
+
+        assert_noop!(
+            Erc721::transfer_bundle(Origin::signed(account(1)), bundle_id, Erc721::account_id()),
+            "Bundles cannot be sent to the module account"
+        );
+    });
+}
+
+#[test]
+fn reclaim_takes_a_bundled_token_out_of_its_bundle() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+        assert_ok!(Erc721::create_bundle(Origin::signed(account(1)), vec![first, second]));
+        let bundle_id = Erc721::bundle_of(first).unwrap();
+        // How a bundle reached the module account before `transfer_bundle` rejected it
+        assert_ok!(Erc721::_move_token(account(1), Erc721::account_id(), first));
+        assert_ok!(Erc721::_move_token(account(1), Erc721::account_id(), second));
+
+        assert_ok!(Erc721::reclaim(Origin::ROOT, first, account(2)));
+
+        assert_eq!(Erc721::owner_of(first), Some(account(2)));
+        assert_eq!(Erc721::bundle_of(first), None);
+        assert_eq!(Erc721::bundle_tokens(bundle_id), vec![second]);
+
+        assert_ok!(Erc721::reclaim(Origin::ROOT, second, account(2)));
+        assert!(Erc721::bundle_tokens(bundle_id).is_empty());
+    });
+}
+
+#[test]
+fn reclaim_needs_no_approval_under_strict_approval() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), Erc721::account_id(), token_id));
+        <StrictApproval<Test>>::put(true);
+
+        assert_ok!(Erc721::reclaim(Origin::ROOT, token_id, account(1)));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(1)));
+    });
+}
+
+#[test]
+fn transfer_with_consent_spends_the_recipients_nonce() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        let to = key_account(1);
+        let consent = sign(1, &(CONSENT_DOMAIN, token_id, account(1), 0u64).encode());
+
+        assert_ok!(Erc721::transfer_with_consent(Origin::signed(account(1)), to, token_id, 0, consent.clone()));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(to));
+        assert_eq!(Erc721::nonce_of(&to), 1);
+
+        // Handed back, the old consent cannot move the token to `to` again
+        assert_ok!(Erc721::_move_token(to, account(1), token_id));
+        assert_noop!(
+            Erc721::transfer_with_consent(Origin::signed(account(1)), to, token_id, 0, consent),
+            "Nonce is stale or already used"
+        );
+    });
+}
+
+#[test]
+fn transfer_with_consent_rejects_signatures_of_other_payloads() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        let to = key_account(1);
+
+        let untagged = sign(1, &token_id.encode());
+        assert_noop!(Erc721::transfer_with_consent(Origin::signed(account(1)), to, token_id, 0, untagged), "Invalid signature");
+
+        let other_owner = sign(1, &(CONSENT_DOMAIN, token_id, account(2), 0u64).encode());
+        assert_noop!(Erc721::transfer_with_consent(Origin::signed(account(1)), to, token_id, 0, other_owner), "Invalid signature");
+    });
+}
+
+#[test]
+fn execute_order_only_accepts_order_payloads() {
+    with_externalities(&mut new_test_ext(), || {
+        let from = key_account(1);
+        assert_ok!(Erc721::mint(Origin::ROOT, from, token(1)));
+
+        let untagged = sign(1, &(account(2), token(1), 10u64).encode());
+        assert_noop!(Erc721::execute_order(Origin::signed(account(3)), from, account(2), token(1), 10, untagged), "Invalid signature");
+
+        let order = sign(1, &(ORDER_DOMAIN, account(2), token(1), 10u64).encode());
+        assert_ok!(Erc721::execute_order(Origin::signed(account(3)), from, account(2), token(1), 10, order.clone()));
+        assert_eq!(Erc721::owner_of(token(1)), Some(account(2)));
+
+        assert_ok!(Erc721::_move_token(account(2), from, token(1)));
+        assert_noop!(
+            Erc721::execute_order(Origin::signed(account(3)), from, account(2), token(1), 10, order),
+            "Order has already been executed"
+        );
+    });
+}
+
+#[test]
+fn exchange_only_accepts_exchange_payloads() {
+    with_externalities(&mut new_test_ext(), || {
+        let seller = key_account(1);
+        assert_ok!(Erc721::mint(Origin::ROOT, seller, token(1)));
+        Balances::set_free_balance(&seller, ENDOWMENT);
+
+        let untagged = sign(1, &(token(1), 300u64, account(2), 10u64).encode());
+        assert_noop!(Erc721::exchange(Origin::signed(account(2)), token(1), seller, 300, 10, untagged), "Invalid signature");
+
+        let sale = sign(1, &(EXCHANGE_DOMAIN, token(1), 300u64, account(2), 10u64).encode());
+        assert_ok!(Erc721::exchange(Origin::signed(account(2)), token(1), seller, 300, 10, sale));
+
+        assert_eq!(Erc721::owner_of(token(1)), Some(account(2)));
+        assert_eq!(Balances::free_balance(&seller), ENDOWMENT + 300);
+        assert_eq!(Balances::free_balance(&account(2)), ENDOWMENT - 300);
+    });
+}
+
+#[test]
+fn exchange_checks_the_transfer_fee_and_the_deadline_before_paying() {
+    with_externalities(&mut new_test_ext_with_fees(10, 5, 0), || {
+        let seller = key_account(1);
+        assert_ok!(Erc721::mint(Origin::ROOT, seller, token(1)));
+        Balances::set_free_balance(&seller, ENDOWMENT);
+
+        let whole_balance = sign(1, &(EXCHANGE_DOMAIN, token(1), ENDOWMENT, account(2), 10u64).encode());
+        assert_noop!(
+            Erc721::exchange(Origin::signed(account(2)), token(1), seller, ENDOWMENT, 10, whole_balance),
+            "Not enough free balance to buy this token"
+        );
+
+        let sale = sign(1, &(EXCHANGE_DOMAIN, token(1), 300u64, account(2), 10u64).encode());
+        System::set_block_number(11);
+        assert_noop!(Erc721::exchange(Origin::signed(account(2)), token(1), seller, 300, 10, sale), "Order has expired");
+    });
+}
+
+#[test]
+fn exchange_rejects_a_price_other_than_the_signed_one() {
+    with_externalities(&mut new_test_ext(), || {
+        let seller = key_account(1);
+        assert_ok!(Erc721::mint(Origin::ROOT, seller, token(1)));
+        Balances::set_free_balance(&seller, ENDOWMENT);
+        let sale = sign(1, &(EXCHANGE_DOMAIN, token(1), 300u64, account(2), 10u64).encode());
+
+        assert_noop!(Erc721::exchange(Origin::signed(account(2)), token(1), seller, 1, 10, sale), "Invalid signature");
+
+        assert_eq!(Erc721::owner_of(token(1)), Some(seller));
+        assert_eq!(Balances::free_balance(&seller), ENDOWMENT);
+        assert_eq!(Balances::free_balance(&account(2)), ENDOWMENT);
+    });
+}
+
+#[test]
+fn exchange_rejects_a_replayed_sale_after_the_token_comes_back() {
+    with_externalities(&mut new_test_ext(), || {
+        let seller = key_account(1);
+        assert_ok!(Erc721::mint(Origin::ROOT, seller, token(1)));
+        Balances::set_free_balance(&seller, ENDOWMENT);
+        let sale = sign(1, &(EXCHANGE_DOMAIN, token(1), 300u64, account(2), 10u64).encode());
+        assert_ok!(Erc721::exchange(Origin::signed(account(2)), token(1), seller, 300, 10, sale.clone()));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(2)), account(2), seller, token(1)));
+
+        assert_noop!(
+            Erc721::exchange(Origin::signed(account(2)), token(1), seller, 300, 10, sale),
+            "Order has already been executed"
+        );
+
+        assert_eq!(Erc721::owner_of(token(1)), Some(seller));
+        assert_eq!(Balances::free_balance(&seller), ENDOWMENT + 300);
+        assert_eq!(Balances::free_balance(&account(2)), ENDOWMENT - 300);
+    });
+}
+
+// Creates a token for `owner` and locks it for `purpose` through `locker`
+fn locked_token_for(owner: H256, locker: H256, purpose: &[u8]) -> H256 {
+    let token_id = create_token_for(owner);
+    assert_ok!(Erc721::set_locker(Origin::ROOT, locker, true));
+    assert_ok!(Erc721::add_lock(Origin::signed(locker), token_id, purpose.to_vec()));
+    token_id
+}
+
+#[test]
+fn a_token_moves_only_once_every_lock_is_removed() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = locked_token_for(account(1), account(3), b"staking");
+        assert_ok!(Erc721::add_lock(Origin::signed(account(3)), token_id, b"governance".to_vec()));
+
+        assert_ok!(Erc721::remove_lock(Origin::signed(account(3)), token_id, b"staking".to_vec()));
+        assert_noop!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id), "Token is locked");
+
+        assert_ok!(Erc721::remove_lock(Origin::signed(account(3)), token_id, b"governance".to_vec()));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+    });
+}
+
+#[test]
+fn locked_tokens_cannot_be_burned() {
+    with_externalities(&mut new_test_ext(), || {
+        <RefundAmount<Test>>::put(100);
+        Balances::set_free_balance(&Erc721::account_id(), 500);
+        let unlocked = create_token_for(account(1));
+        let token_id = locked_token_for(account(1), account(3), b"staking");
+
+        assert_noop!(Erc721::burn_many(Origin::signed(account(1)), vec![unlocked, token_id]), "Token is locked");
+        assert_noop!(Erc721::burn_for_refund(Origin::signed(account(1)), token_id), "Token is locked");
+        assert_noop!(Erc721::burn_all_of(Origin::ROOT, account(1)), "Token is locked");
+        assert_noop!(Erc721::_burn(token_id), "Token is locked");
+
+        assert_eq!(Erc721::token_locks(token_id), vec![b"staking".to_vec()]);
+    });
+}
+
+#[test]
+fn remap_token_id_moves_the_mint_block_and_transfer_records() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(3);
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token(1)));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(2)), account(2), account(1), token(1)));
+
+        assert_ok!(Erc721::remap_token_id(Origin::ROOT, token(1), token(2)));
+
+        assert_eq!(Erc721::owner_of(token(2)), Some(account(1)));
+        assert_eq!(Erc721::mint_block_of(token(2)), Some(3));
+        assert_eq!(Erc721::tokens_minted_in_block(3), vec![token(2)]);
+        assert_eq!(Erc721::history_of(token(2)), vec![account(1), account(2), account(1)]);
+        assert_eq!(Erc721::sent_transfers(&account(1)), vec![token(2)]);
+        assert_eq!(Erc721::received_transfers(&account(1)), vec![token(2)]);
+        assert_eq!(Erc721::sent_transfers(&account(2)), vec![token(2)]);
+        assert_eq!(Erc721::received_transfers(&account(2)), vec![token(2)]);
+        assert_eq!(Erc721::mint_block_of(token(1)), None);
+        assert!(!<OwnershipHistory<Test>>::exists(token(1)));
+    });
+}
+
+#[test]
+fn remap_token_id_adds_no_entries_the_old_id_did_not_have() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+
+        assert_ok!(Erc721::remap_token_id(Origin::ROOT, token(1), token(2)));
+
+        assert!(!<TokenLocks<Test>>::exists(token(2)));
+        assert!(!<WhitelistedRecipients<Test>>::exists(token(2)));
+        assert!(!<OfferBidders<Test>>::exists(token(2)));
+        assert!(!<SentTransfers<Test>>::exists(account(1)));
+        assert!(!<ReceivedTransfers<Test>>::exists(account(1)));
+    });
+}
+
+#[test]
+fn approve_many_approves_every_token() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+
+        assert_ok!(Erc721::approve_many(Origin::signed(account(1)), account(2), vec![first, second]));
+
+        assert_eq!(Erc721::get_approved(first), Some(account(2)));
+        assert_eq!(Erc721::get_approved(second), Some(account(2)));
+    });
+}
+
+#[test]
+fn approve_many_rejects_the_whole_batch_for_one_token_of_another_owner() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+        let foreign = create_token_for(account(3));
+
+        assert_noop!(
+            Erc721::approve_many(Origin::signed(account(1)), account(2), vec![first, second, foreign]),
+            "You are not allowed to approve for this token"
+        );
+
+        assert_eq!(Erc721::get_approved(first), None);
+        assert_eq!(Erc721::get_approved(second), None);
+        assert_eq!(Erc721::get_approved(foreign), None);
+    });
+}
+
+#[test]
+fn batch_calls_reject_duplicate_token_ids() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+
+        assert_noop!(
+            Erc721::approve_many(Origin::signed(account(1)), account(2), vec![first, second, first]),
+            "Duplicate token id in batch"
+        );
+        assert_noop!(Erc721::create_bundle(Origin::signed(account(1)), vec![first, first]), "Duplicate token id in batch");
+        assert_noop!(Erc721::burn_many(Origin::signed(account(1)), vec![second, second]), "Duplicate token id in batch");
+    });
+}
+
+#[test]
+fn a_transfer_to_the_owner_is_a_no_op_that_clears_the_approval() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(2), token_id));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(1), token_id));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(1)));
+        assert_eq!(Erc721::balance_of(&account(1)), 1);
+        assert_eq!(Erc721::get_approved(token_id), None);
+        assert_eq!(last_event(), Some(RawEvent::Transfer(Some(account(1)), Some(account(1)), token_id)));
+    });
+}
+
+#[test]
+fn a_transfer_to_the_owner_keeps_the_approval_when_transfers_do() {
+    with_externalities(&mut new_test_ext(), || {
+        <ClearApprovalOnTransfer<Test>>::put(false);
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(2), token_id));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(1), token_id));
+
+        assert_eq!(Erc721::get_approved(token_id), Some(account(2)));
+    });
+}
+
+#[test]
+fn a_transfer_to_the_owner_can_be_rejected() {
+    with_externalities(&mut new_test_ext(), || {
+        <RejectSelfTransfer<Test>>::put(true);
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(
+            Erc721::transfer_from(Origin::signed(account(1)), account(1), account(1), token_id),
+            "Cannot transfer to current owner"
+        );
+    });
+}
+
+#[test]
+fn a_single_use_approval_is_spent_by_one_transfer() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve_single_use(Origin::signed(account(1)), account(2), token_id));
+        assert_eq!(Erc721::single_use_approval(token_id), Some(account(2)));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(2)), account(1), account(1), token_id));
+
+        assert_eq!(Erc721::single_use_approval(token_id), None);
+        assert_noop!(Erc721::transfer_from(Origin::signed(account(2)), account(1), account(3), token_id), "You do not own this token");
+    });
+}
+
+#[test]
+fn a_single_use_approval_works_in_every_spender_call() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_ok!(Erc721::approve_single_use(Origin::signed(account(1)), account(2), token_id));
+        assert_ok!(Erc721::operator_transfer(Origin::signed(account(2)), account(1), token_id));
+        assert_eq!(Erc721::single_use_approval(token_id), None);
+        assert_noop!(Erc721::operator_transfer(Origin::signed(account(2)), account(3), token_id), "You do not own this token");
+
+        assert_ok!(Erc721::approve_single_use(Origin::signed(account(1)), account(2), token_id));
+        assert_ok!(Erc721::transfer_with_memo(Origin::signed(account(2)), account(1), token_id, b"memo".to_vec()));
+        assert_eq!(Erc721::single_use_approval(token_id), None);
+
+        assert_ok!(Erc721::approve_single_use(Origin::signed(account(1)), account(2), token_id));
+        assert_ok!(Erc721::transfer_many_best_effort(Origin::signed(account(2)), vec![(account(1), account(1), token_id)]));
+        assert_eq!(Erc721::single_use_approval(token_id), None);
+        assert_eq!(last_event(), Some(RawEvent::BatchPartial(1, 0)));
+
+        assert_ok!(Erc721::create_bundle(Origin::signed(account(1)), vec![token_id]));
+        let bundle_id = Erc721::bundle_of(token_id).unwrap();
+        assert_ok!(Erc721::approve_single_use(Origin::signed(account(1)), account(2), token_id));
+        assert_ok!(Erc721::transfer_bundle(Origin::signed(account(2)), bundle_id, account(3)));
+        assert_eq!(Erc721::owner_of(token_id), Some(account(3)));
+        assert_eq!(Erc721::single_use_approval(token_id), None);
+    });
+}
+
+#[test]
+fn the_module_account_is_fixed_and_can_hold_tokens() {
+    with_externalities(&mut new_test_ext(), || {
+        let module_account = Erc721::account_id();
+        assert!(module_account != H256::default());
+        assert_eq!(module_account, Erc721::account_id());
+
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), module_account, token_id));
+        assert_eq!(Erc721::owner_of(token_id), Some(module_account));
+        assert_eq!(Erc721::balance_of(&module_account), 1);
+
+        assert_ok!(Erc721::reclaim(Origin::ROOT, token_id, account(2)));
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+        assert_eq!(Erc721::balance_of(&module_account), 0);
+        assert_eq!(last_event(), Some(RawEvent::Reclaimed(token_id, account(2))));
+    });
+}
+
+#[test]
+fn history_of_lists_every_owner_and_survives_a_burn() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(2)), account(2), account(3), token_id));
+
+        assert_eq!(Erc721::history_of(token_id), vec![account(1), account(2), account(3)]);
+
+        assert_ok!(Erc721::burn_many(Origin::signed(account(3)), vec![token_id]));
+        assert_eq!(Erc721::history_of(token_id), vec![account(1), account(2), account(3)]);
+    });
+}
+
+#[test]
+fn a_default_operator_is_approved_until_the_owner_opts_out() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(2));
+        assert_ok!(Erc721::set_default_operator(Origin::ROOT, account(4), true));
+
+        assert_ok!(Erc721::operator_transfer(Origin::signed(account(4)), account(3), first));
+        assert_eq!(Erc721::owner_of(first), Some(account(3)));
+
+        assert_ok!(Erc721::revoke_default_operator(Origin::signed(account(2)), account(4)));
+        assert_noop!(Erc721::operator_transfer(Origin::signed(account(4)), account(3), second), "You do not own this token");
+        assert!(Erc721::is_approved_or_owner(&account(4), first));
+    });
+}
+
+#[test]
+fn revoke_default_operator_rejects_other_accounts() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_noop!(
+            Erc721::revoke_default_operator(Origin::signed(account(1)), account(4)),
+            "This account is not a default operator"
+        );
+    });
+}
+
+#[test]
+fn the_transfer_policy_can_reject_a_transfer() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        MockTransferPolicy::block_recipient(Some(account(2)));
+
+        assert_noop!(
+            Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id),
+            "Transfer policy rejected the recipient"
+        );
+        assert_eq!(Erc721::try_transfer(account(1), account(1), account(2), token_id), TransferOutcome::Rejected);
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(3), token_id));
+    });
+}
+
+#[test]
+fn burn_many_burns_every_token() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+        let kept = create_token_for(account(1));
+
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![first, second]));
+
+        assert_eq!(Erc721::owner_of(first), None);
+        assert_eq!(Erc721::owner_of(second), None);
+        assert_eq!(Erc721::balance_of(&account(1)), 1);
+        assert_eq!(Erc721::token_of_owner_by_index((account(1), 0)), kept);
+        assert_eq!(Erc721::total_supply(), 1);
+        assert!(erc721_events().contains(&RawEvent::Burned(account(1), first)));
+        assert!(erc721_events().contains(&RawEvent::Transfer(Some(account(1)), None, second)));
+    });
+}
+
+#[test]
+fn burn_many_rejects_the_whole_batch() {
+    with_externalities(&mut new_test_ext(), || {
+        let own = create_token_for(account(1));
+        let other = create_token_for(account(2));
+
+        assert_noop!(Erc721::burn_many(Origin::signed(account(1)), vec![own, other]), "You do not own this token");
+
+        <MaxBatchSize<Test>>::put(1);
+        let second = create_token_for(account(1));
+        assert_noop!(Erc721::burn_many(Origin::signed(account(1)), vec![own, second]), "Batch too large");
+    });
+}
+
+#[test]
+fn the_per_creator_cap_limits_each_creator() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::set_per_creator_cap(Origin::ROOT, Some(2)));
+        let first = create_token_for(account(1));
+        create_token_for(account(1));
+
+        assert_noop!(Erc721::create_token(Origin::signed(account(1))), "Creator has reached the mint cap");
+        create_token_for(account(2));
+
+        // Without `BurnRefundsCap` a burned token still counts
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![first]));
+        assert_eq!(Erc721::creator_mint_count(&account(1)), 2);
+        assert_noop!(Erc721::create_token(Origin::signed(account(1))), "Creator has reached the mint cap");
+    });
+}
+
+#[test]
+fn a_burn_frees_a_cap_slot_when_burn_refunds_cap_is_set() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::set_per_creator_cap(Origin::ROOT, Some(1)));
+        assert_ok!(Erc721::set_burn_refunds_cap(Origin::ROOT, true));
+        let token_id = create_token_for(account(1));
+
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![token_id]));
+
+        assert_eq!(Erc721::creator_mint_count(&account(1)), 0);
+        create_token_for(account(1));
+    });
+}
+
+#[test]
+fn token_info_returns_the_owner_and_approval() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_eq!(Erc721::token_info(token_id), Some((account(1), None)));
+
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(2), token_id));
+        assert_eq!(Erc721::token_info(token_id), Some((account(1), Some(account(2)))));
+
+        assert_eq!(Erc721::token_info(token(99)), None);
+    });
+}
+
+#[test]
+fn create_token_pays_the_mint_price_to_the_module_account() {
+    with_externalities(&mut new_test_ext(), || {
+        create_token_for(account(1));
+        assert_eq!(Balances::free_balance(&account(1)), ENDOWMENT);
+
+        assert_ok!(Erc721::set_mint_price(Origin::ROOT, Some(250)));
+        create_token_for(account(1));
+
+        assert_eq!(Balances::free_balance(&account(1)), ENDOWMENT - 250);
+        assert_eq!(Balances::free_balance(&Erc721::account_id()), 250);
+    });
+}
+
+#[test]
+fn create_token_rejects_minters_who_cannot_pay() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::set_mint_price(Origin::ROOT, Some(ENDOWMENT + 1)));
+
+        assert_noop!(Erc721::create_token(Origin::signed(account(1))), "Not enough free balance to pay the mint price");
+        assert_eq!(Erc721::total_supply(), 0);
+    });
+}
+
+#[test]
+fn transfer_with_memo_emits_the_memo() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_ok!(Erc721::transfer_with_memo(Origin::signed(account(1)), account(2), token_id, b"invoice 42".to_vec()));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+        assert_eq!(
+            last_event(),
+            Some(RawEvent::TransferWithMemo(account(1), account(2), token_id, b"invoice 42".to_vec()))
+        );
+    });
+}
+
+#[test]
+fn transfer_with_memo_rejects_long_memos_and_unapproved_senders() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(
+            Erc721::transfer_with_memo(Origin::signed(account(1)), account(2), token_id, vec![0; MAX_MEMO_LENGTH + 1]),
+            "Memo is too long"
+        );
+        assert_noop!(
+            Erc721::transfer_with_memo(Origin::signed(account(2)), account(2), token_id, b"mine".to_vec()),
+            "You do not own this token"
+        );
+    });
+}
+
+#[test]
+fn admin_clear_approval_drops_every_approval_of_a_token() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(2), token_id));
+        assert_ok!(Erc721::approve_single_use(Origin::signed(account(1)), account(3), token_id));
+
+        assert_ok!(Erc721::admin_clear_approval(Origin::ROOT, token_id));
+
+        assert_eq!(Erc721::get_approved(token_id), None);
+        assert_eq!(Erc721::single_use_approval(token_id), None);
+        assert_eq!(last_event(), Some(RawEvent::ApprovalCleared(token_id)));
+    });
+}
+
+#[test]
+fn admin_clear_approval_is_root_only() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(Erc721::admin_clear_approval(Origin::signed(account(1)), token_id), "bad origin: expected to be a root origin");
+        assert_noop!(Erc721::admin_clear_approval(Origin::ROOT, token(99)), "No owner for this token");
+    });
+}
+
+#[test]
+fn lifecycle_events_put_the_token_id_last() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(3), token(1)));
+        assert_eq!(last_event(), Some(RawEvent::Approval(account(1), account(3), token(1))));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token(1)));
+        assert_eq!(last_event(), Some(RawEvent::Transfer(Some(account(1)), Some(account(2)), token(1))));
+        assert_ok!(Erc721::burn_many(Origin::signed(account(2)), vec![token(1)]));
+        assert_eq!(last_event(), Some(RawEvent::Burned(account(2), token(1))));
+
+        assert_eq!(erc721_events(), vec![
+            RawEvent::Transfer(None, Some(account(1)), token(1)),
+            RawEvent::Minted(account(1), token(1)),
+            RawEvent::Approval(account(1), account(3), token(1)),
+            RawEvent::Transfer(Some(account(1)), Some(account(2)), token(1)),
+            RawEvent::Transfer(Some(account(2)), None, token(1)),
+            RawEvent::Burned(account(2), token(1)),
+        ]);
+    });
+}
+
+#[test]
+fn a_burn_emits_transfer_then_burned() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![token_id]));
+
+        let events = erc721_events();
+        assert_eq!(events[events.len() - 2..].to_vec(), vec![
+            RawEvent::Transfer(Some(account(1)), None, token_id),
+            RawEvent::Burned(account(1), token_id),
+        ]);
+    });
+}
+
+#[test]
+fn reserved_token_ids_are_left_to_the_root_mint() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::reserve_token_id(Origin::ROOT, token(1)));
+        assert!(Erc721::is_reserved(token(1)));
+
+        assert_noop!(Erc721::mint_and_approve(Origin::signed(account(1)), token(1), account(2)), "Token id is reserved");
+
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+        assert_noop!(Erc721::reserve_token_id(Origin::ROOT, token(1)), "Token already exists");
+    });
+}
+
+#[test]
+fn holds_any_follows_the_balance() {
+    with_externalities(&mut new_test_ext(), || {
+        assert!(!Erc721::holds_any(&account(1)));
+
+        let token_id = create_token_for(account(1));
+        assert!(Erc721::holds_any(&account(1)));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+        assert!(!Erc721::holds_any(&account(1)));
+        assert!(Erc721::holds_any(&account(2)));
+    });
+}
+
+#[test]
+fn create_token_derives_the_id_with_the_token_id_hasher() {
+    with_externalities(&mut new_test_ext(), || {
+        let expected = (System::random_seed(), account(1), 0u64).using_encoded(<Test as Trait>::TokenIdHasher::hash);
+
+        assert_eq!(create_token_for(account(1)), expected);
+    });
+}
+
+#[test]
+fn a_whitelisted_token_only_moves_to_its_recipients() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::add_whitelisted_recipient(Origin::signed(account(1)), token_id, account(2)));
+
+        assert_noop!(
+            Erc721::transfer_from(Origin::signed(account(1)), account(1), account(3), token_id),
+            "Recipient is not whitelisted for this token"
+        );
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+    });
+}
+
+#[test]
+fn removing_the_last_whitelisted_recipient_lifts_the_restriction() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::add_whitelisted_recipient(Origin::signed(account(1)), token_id, account(2)));
+        assert_noop!(
+            Erc721::add_whitelisted_recipient(Origin::signed(account(1)), token_id, account(2)),
+            "Recipient is already whitelisted"
+        );
+        assert_noop!(
+            Erc721::remove_whitelisted_recipient(Origin::signed(account(2)), token_id, account(2)),
+            "You do not own this token"
+        );
+
+        assert_ok!(Erc721::remove_whitelisted_recipient(Origin::signed(account(1)), token_id, account(2)));
+
+        assert!(Erc721::whitelisted_recipients(token_id).is_empty());
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(3), token_id));
+    });
+}
+
+#[test]
+fn consume_nonce_only_accepts_the_current_nonce() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_eq!(Erc721::nonce_of(&account(1)), 0);
+
+        assert_noop!(Erc721::consume_nonce(&account(1), 1), "Nonce is stale or already used");
+        assert_ok!(Erc721::consume_nonce(&account(1), 0));
+        assert_noop!(Erc721::consume_nonce(&account(1), 0), "Nonce is stale or already used");
+        assert_ok!(Erc721::consume_nonce(&account(1), 1));
+
+        assert_eq!(Erc721::nonce_of(&account(1)), 2);
+        assert_eq!(Erc721::nonce_of(&account(2)), 0);
+    });
+}
+
+#[test]
+fn owners_can_only_approve_max_operators_per_owner_operators() {
+    with_externalities(&mut new_test_ext(), || {
+        <MaxOperatorsPerOwner<Test>>::put(2);
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(2), true));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(3), true));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(3), true));
+
+        assert_noop!(
+            Erc721::set_approval_for_all(Origin::signed(account(1)), account(4), true),
+            "Owner has approved the maximum number of operators"
+        );
+
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(2), false));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(4), true));
+        assert_eq!(Erc721::approved_operators(&account(1)), vec![account(3), account(4)]);
+    });
+}
+
+#[test]
+fn reclaim_only_moves_tokens_of_the_module_account() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(Erc721::reclaim(Origin::ROOT, token_id, account(2)), "Token is not held by the module account");
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), Erc721::account_id(), token_id));
+        assert_noop!(Erc721::reclaim(Origin::signed(account(1)), token_id, account(1)), "bad origin: expected to be a root origin");
+    });
+}
+
+#[test]
+fn mint_and_approve_mints_and_approves_in_one_call() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::mint_and_approve(Origin::signed(account(1)), token(1), account(2)));
+
+        assert_eq!(Erc721::owner_of(token(1)), Some(account(1)));
+        assert_eq!(Erc721::get_approved(token(1)), Some(account(2)));
+        assert_eq!(last_event(), Some(RawEvent::Approval(account(1), account(2), token(1))));
+
+        assert_noop!(Erc721::mint_and_approve(Origin::signed(account(1)), token(2), account(1)), "Owner is implicitly approved");
+    });
+}
+
+#[test]
+fn tokens_minted_in_block_lists_each_blocks_mints() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(1);
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(2)));
+        System::set_block_number(2);
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(3)));
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![token(1)]));
+
+        assert_eq!(Erc721::tokens_minted_in_block(1), vec![token(1), token(2)]);
+        assert_eq!(Erc721::tokens_minted_in_block(2), vec![token(3)]);
+        assert!(Erc721::tokens_minted_in_block(3).is_empty());
+    });
+}
+
+#[test]
+fn execute_order_rejects_expired_and_foreign_orders() {
+    with_externalities(&mut new_test_ext(), || {
+        let from = key_account(1);
+        assert_ok!(Erc721::mint(Origin::ROOT, from, token(1)));
+        let order = sign(1, &(ORDER_DOMAIN, account(2), token(1), 10u64).encode());
+        let foreign = sign(2, &(ORDER_DOMAIN, account(2), token(1), 10u64).encode());
+
+        assert_noop!(Erc721::execute_order(Origin::signed(account(3)), from, account(2), token(1), 10, foreign), "Invalid signature");
+
+        System::set_block_number(11);
+        assert_noop!(Erc721::execute_order(Origin::signed(account(3)), from, account(2), token(1), 10, order), "Order has expired");
+    });
+}
+
+#[test]
+fn owned_tokens_raw_lists_the_tokens_in_enumeration_order() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+
+        assert_eq!(Erc721::owned_tokens_raw(&account(1)), vec![first, second]);
+        assert!(Erc721::owned_tokens_raw(&account(2)).is_empty());
+    });
+}
+
+#[test]
+fn approvals_survive_transfers_when_clear_approval_on_transfer_is_off() {
+    with_externalities(&mut new_test_ext(), || {
+        <ClearApprovalOnTransfer<Test>>::put(false);
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(3), token_id));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+
+        assert_eq!(Erc721::get_approved(token_id), Some(account(3)));
+    });
+}
+
+#[test]
+fn try_transfer_reports_why_a_transfer_did_not_happen() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_eq!(Erc721::try_transfer(account(1), account(1), account(2), token(99)), TransferOutcome::NoToken);
+        assert_eq!(Erc721::try_transfer(account(1), account(2), account(3), token_id), TransferOutcome::NotOwner);
+        assert_eq!(Erc721::try_transfer(account(2), account(1), account(2), token_id), TransferOutcome::NotApproved);
+        assert_eq!(Erc721::owner_of(token_id), Some(account(1)));
+
+        assert_eq!(Erc721::try_transfer(account(1), account(1), account(2), token_id), TransferOutcome::Success);
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+    });
+}
+
+#[test]
+fn transfer_many_best_effort_skips_failed_transfers() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(2));
+
+        assert_ok!(Erc721::transfer_many_best_effort(Origin::signed(account(1)), vec![
+            (account(1), account(3), first),
+            (account(2), account(3), second),
+        ]));
+
+        assert_eq!(Erc721::owner_of(first), Some(account(3)));
+        assert_eq!(Erc721::owner_of(second), Some(account(2)));
+        assert_eq!(last_event(), Some(RawEvent::BatchPartial(1, 1)));
+    });
+}
+
+#[test]
+fn holders_are_tracked_as_balances_change() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        create_token_for(account(2));
+        create_token_for(account(3));
+        assert_eq!(Erc721::holder_count(), 3);
+        assert_eq!(Erc721::holders_paged(0, 10), vec![account(1), account(2), account(3)]);
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(3), token_id));
+
+        assert!(!Erc721::is_holder(&account(1)));
+        assert_eq!(Erc721::holder_count(), 2);
+        assert_eq!(Erc721::holders_paged(0, 10), vec![account(3), account(2)]);
+        assert_eq!(Erc721::holders_paged(1, 1), vec![account(2)]);
+        assert!(Erc721::holders_paged(2, 10).is_empty());
+    });
+}
+
+#[test]
+fn an_escrow_approved_token_can_be_pulled_into_the_module_account() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_noop!(Erc721::pull_into_escrow(token_id), "Token has not been approved for escrow");
+
+        assert_ok!(Erc721::approve_escrow(Origin::signed(account(1)), token_id));
+        assert_eq!(Erc721::get_approved(token_id), Some(Erc721::account_id()));
+        assert_eq!(Erc721::escrow_depositor(token_id), Some(account(1)));
+
+        assert_ok!(Erc721::pull_into_escrow(token_id));
+        assert_eq!(Erc721::owner_of(token_id), Some(Erc721::account_id()));
+        assert_eq!(Erc721::escrow_depositor(token_id), Some(account(1)));
+
+        assert_ok!(Erc721::reclaim(Origin::ROOT, token_id, account(1)));
+        assert_eq!(Erc721::escrow_depositor(token_id), None);
+    });
+}
+
+#[test]
+fn approve_escrow_is_only_for_the_owner() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(Erc721::approve_escrow(Origin::signed(account(2)), token_id), "You do not own this token");
+    });
+}
+
+#[test]
+fn list_for_sale_is_for_owners_and_lists_once() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(Erc721::list_for_sale(Origin::signed(account(2)), token_id, 500), "You do not own this token");
+        assert_ok!(Erc721::list_for_sale(Origin::signed(account(1)), token_id, 500));
+        assert_noop!(Erc721::list_for_sale(Origin::signed(account(1)), token_id, 600), "Token is already listed, use update_price");
+
+        assert_eq!(Erc721::sale_price(token_id), Some(500));
+        assert_eq!(Erc721::get_approved(token_id), Some(Erc721::account_id()));
+    });
+}
+
+#[test]
+fn buy_rejects_invalid_purchases() {
+    with_externalities(&mut new_test_ext(), || {
+        let unlisted = create_token_for(account(1));
+        let token_id = list_token_for(account(1), 500, 0);
+
+        assert_noop!(Erc721::buy(Origin::signed(account(2)), unlisted, 500), "Token is not for sale");
+        assert_noop!(Erc721::buy(Origin::signed(account(1)), token_id, 500), "You already own this token");
+
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(3), token_id));
+        assert_noop!(Erc721::buy(Origin::signed(account(2)), token_id, 500), "Listing is no longer approved");
+    });
+}
+
+#[test]
+fn highest_offer_follows_the_best_open_offer() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::make_offer(Origin::signed(account(2)), token_id, 300));
+        assert_ok!(Erc721::make_offer(Origin::signed(account(3)), token_id, 200));
+        assert_eq!(Erc721::highest_offer(token_id), Some((account(2), 300)));
+
+        assert_ok!(Erc721::make_offer(Origin::signed(account(4)), token_id, 400));
+        assert_eq!(Erc721::highest_offer(token_id), Some((account(4), 400)));
+
+        assert_ok!(Erc721::withdraw_offer(Origin::signed(account(3)), token_id));
+        assert_eq!(Erc721::highest_offer(token_id), Some((account(4), 400)));
+    });
+}
+
+#[test]
+fn make_offer_rejects_invalid_offers() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(Erc721::make_offer(Origin::signed(account(1)), token_id, 300), "You already own this token");
+        assert_noop!(Erc721::make_offer(Origin::signed(account(2)), token_id, 0), "Offer must be more than zero");
+        assert_noop!(Erc721::withdraw_offer(Origin::signed(account(2)), token_id), "You have no offer for this token");
+
+        assert_ok!(Erc721::make_offer(Origin::signed(account(2)), token_id, 300));
+        assert_noop!(Erc721::make_offer(Origin::signed(account(2)), token_id, 400), "Withdraw your current offer first");
+    });
+}
+
+#[test]
+fn transfers_are_recorded_for_sender_and_recipient() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+        assert!(Erc721::received_transfers(&account(1)).is_empty());
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), first));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), second));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(2)), account(2), account(3), first));
+
+        assert_eq!(Erc721::sent_transfers(&account(1)), vec![first, second]);
+        assert_eq!(Erc721::received_transfers(&account(2)), vec![first, second]);
+        assert_eq!(Erc721::sent_transfers(&account(2)), vec![first]);
+        assert_eq!(Erc721::received_transfers(&account(3)), vec![first]);
+    });
+}
+
+#[test]
+fn owner_proof_points_at_the_owner_in_storage() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        let (owner, storage_key) = Erc721::owner_proof(token_id).unwrap();
+
+        assert_eq!(owner, account(1));
+        let stored = runtime_io::storage(&storage_key).unwrap();
+        assert_eq!(H256::decode(&mut &stored[..]), Some(account(1)));
+        assert_eq!(Erc721::owner_proof(token(99)), None);
+    });
+}
+
+#[test]
+fn dissolve_bundle_frees_its_tokens() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+        assert_ok!(Erc721::create_bundle(Origin::signed(account(1)), vec![first, second]));
+        let bundle_id = Erc721::bundle_of(first).unwrap();
+
+        assert_noop!(Erc721::dissolve_bundle(Origin::signed(account(2)), bundle_id), "You do not own this bundle");
+        assert_ok!(Erc721::dissolve_bundle(Origin::signed(account(1)), bundle_id));
+
+        assert_eq!(Erc721::bundle_of(first), None);
+        assert!(Erc721::bundle_tokens(bundle_id).is_empty());
+        assert_eq!(last_event(), Some(RawEvent::BundleDissolved(account(1), bundle_id)));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), first));
+        assert_noop!(Erc721::dissolve_bundle(Origin::signed(account(1)), bundle_id), "Bundle does not exist");
+    });
+}
+
+#[test]
+fn moving_a_middle_token_keeps_both_enumerations_consistent() {
+    with_externalities(&mut new_test_ext(), || {
+        let first = create_token_for(account(1));
+        let middle = create_token_for(account(1));
+        let last = create_token_for(account(1));
+        let other = create_token_for(account(2));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), middle));
+
+        assert_eq!(Erc721::balance_of(&account(1)), 2);
+        assert_eq!(Erc721::owned_tokens_raw(&account(1)), vec![first, last]);
+        assert_eq!(Erc721::balance_of(&account(2)), 2);
+        assert_eq!(Erc721::owned_tokens_raw(&account(2)), vec![other, middle]);
+    });
+}
+
+#[test]
+fn burning_a_middle_token_keeps_both_enumerations_contiguous() {
+    with_externalities(&mut new_test_ext(), || {
+        for n in 1..4 {
+            assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(n)));
+        }
+
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![token(2)]));
+
+        assert_eq!(Erc721::balance_of(&account(1)), 2);
+        assert_eq!(Erc721::token_of_owner_by_index((account(1), 0)), token(1));
+        assert_eq!(Erc721::token_of_owner_by_index((account(1), 1)), token(3));
+        assert_eq!(<OwnedTokensIndex<Test>>::get(token(1)), 0);
+        assert_eq!(<OwnedTokensIndex<Test>>::get(token(3)), 1);
+        assert!(!<OwnedTokens<Test>>::exists((account(1), 2)));
+        assert!(!<OwnedTokensIndex<Test>>::exists(token(2)));
+
+        assert_eq!(Erc721::total_supply(), 2);
+        assert_eq!(Erc721::token_by_index(0), token(1));
+        assert_eq!(Erc721::token_by_index(1), token(3));
+        assert_eq!(<AllTokensIndex<Test>>::get(token(1)), 0);
+        assert_eq!(<AllTokensIndex<Test>>::get(token(3)), 1);
+        assert!(!<AllTokens<Test>>::exists(2));
+        assert!(!<AllTokensIndex<Test>>::exists(token(2)));
+    });
+}
+
+#[test]
+fn snapshot_holders_records_balances_under_a_tag() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        create_token_for(account(1));
+        create_token_for(account(2));
+
+        assert_ok!(Erc721::snapshot_holders(Origin::ROOT, 7));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(3), token_id));
+
+        assert_eq!(Erc721::snapshot_balance((7, account(1))), 2);
+        assert_eq!(Erc721::snapshot_balance((7, account(2))), 1);
+        assert_eq!(Erc721::snapshot_balance((7, account(3))), 0);
+        assert!(Erc721::is_snapshot_taken(7));
+        assert_eq!(erc721_events().iter().filter(|e| **e == RawEvent::SnapshotTaken(7)).count(), 1);
+        assert_noop!(Erc721::snapshot_holders(Origin::ROOT, 7), "A snapshot with this tag already exists");
+    });
+}
+
+#[test]
+fn a_burn_clears_every_per_token_entry() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::add_whitelisted_recipient(Origin::signed(account(1)), token_id, account(2)));
+        assert_ok!(Erc721::create_bundle(Origin::signed(account(1)), vec![token_id]));
+        let bundle_id = Erc721::bundle_of(token_id).unwrap();
+        assert_ok!(Erc721::make_offer(Origin::signed(account(2)), token_id, 300));
+        assert_ok!(Erc721::approve_escrow(Origin::signed(account(1)), token_id));
+        assert_ok!(Erc721::list_for_sale(Origin::signed(account(1)), token_id, 500));
+        assert_ok!(Erc721::approve_single_use(Origin::signed(account(1)), account(3), token_id));
+
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![token_id]));
+
+        assert!(!<TokenOwner<Test>>::exists(token_id));
+        assert!(!<OwnedTokensIndex<Test>>::exists(token_id));
+        assert!(!<AllTokensIndex<Test>>::exists(token_id));
+        assert_eq!(Erc721::get_approved(token_id), None);
+        assert_eq!(Erc721::single_use_approval(token_id), None);
+        assert_eq!(Erc721::sale_price(token_id), None);
+        assert_eq!(Erc721::escrow_depositor(token_id), None);
+        assert!(Erc721::whitelisted_recipients(token_id).is_empty());
+        assert!(!Erc721::is_whitelisted_recipient((token_id, account(2))));
+        assert_eq!(Erc721::bundle_of(token_id), None);
+        assert!(!<Bundles<Test>>::exists(bundle_id));
+        assert_eq!(Erc721::offer_of((token_id, account(2))), 0);
+        assert!(Erc721::offer_bidders(token_id).is_empty());
+        assert_eq!(Erc721::highest_offer(token_id), None);
+        assert_eq!(Balances::reserved_balance(&account(2)), 0);
+        assert_eq!(Balances::free_balance(&account(2)), ENDOWMENT);
+        assert_eq!(Erc721::creator_of(token_id), None);
+        assert_eq!(Erc721::history_of(token_id), vec![account(1)]);
+    });
+}
+
+#[test]
+fn operator_transfer_moves_the_token_from_its_owner() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+
+        assert_noop!(Erc721::operator_transfer(Origin::signed(account(2)), account(3), token_id), "You do not own this token");
+
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(2), true));
+        assert_ok!(Erc721::operator_transfer(Origin::signed(account(2)), account(3), token_id));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(3)));
+        assert_noop!(Erc721::operator_transfer(Origin::signed(account(2)), account(3), token(99)), "No owner for this token");
+    });
+}
+
+#[test]
+fn transfer_checks_approvals_against_the_owner_read_at_the_start() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(3), true));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(2)), account(4), true));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(3)), account(1), account(2), token_id));
+
+        // The first owner's operator lost its say with the transfer, the new owner's operator has it
+        assert_noop!(Erc721::operator_transfer(Origin::signed(account(3)), account(3), token_id), "You do not own this token");
+        assert_noop!(
+            Erc721::transfer_from(Origin::signed(account(4)), account(1), account(4), token_id),
+            "'from' account does not own this token"
+        );
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(4)), account(2), account(4), token_id));
+        assert_eq!(Erc721::owner_of(token_id), Some(account(4)));
+    });
+}
+
+#[test]
+fn stable_ordering_keeps_the_insertion_order() {
+    with_externalities(&mut new_test_ext(), || {
+        <StableOrdering<Test>>::put(true);
+        let first = create_token_for(account(1));
+        let second = create_token_for(account(1));
+        let third = create_token_for(account(1));
+        let fourth = create_token_for(account(1));
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), second));
+
+        assert_eq!(Erc721::owned_tokens_raw(&account(1)), vec![first, third, fourth]);
+        assert_eq!(<OwnedTokensIndex<Test>>::get(third), 1);
+        assert_eq!(<OwnedTokensIndex<Test>>::get(fourth), 2);
+
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), third));
+        assert_eq!(Erc721::owned_tokens_raw(&account(1)), vec![first, fourth]);
+    });
+}
+
+#[test]
+fn is_approved_or_owner_matches_the_transfer_rules() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(2), token_id));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(3), true));
+
+        assert!(Erc721::is_approved_or_owner(&account(1), token_id));
+        assert!(Erc721::is_approved_or_owner(&account(2), token_id));
+        assert!(Erc721::is_approved_or_owner(&account(3), token_id));
+        assert!(!Erc721::is_approved_or_owner(&account(4), token_id));
+        assert!(!Erc721::is_approved_or_owner(&account(1), token(99)));
+    });
+}
+
+#[test]
+fn is_approved_or_owner_counts_a_single_use_approval_until_it_is_spent() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve_single_use(Origin::signed(account(1)), account(4), token_id));
+
+        assert!(Erc721::is_approved_or_owner(&account(4), token_id));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(4)), account(1), account(2), token_id));
+
+        assert!(!Erc721::is_approved_or_owner(&account(4), token_id));
+    });
+}
+
+#[test]
+fn reset_all_approvals_revokes_every_operator_of_the_sender() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(2), true));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(3), true));
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(4)), account(2), true));
+
+        assert_ok!(Erc721::reset_all_approvals(Origin::signed(account(1))));
+
+        assert!(!Erc721::is_approved_for_all((account(1), account(2))));
+        assert!(!Erc721::is_approved_for_all((account(1), account(3))));
+        assert!(Erc721::approved_operators(&account(1)).is_empty());
+        assert!(Erc721::is_approved_for_all((account(4), account(2))));
+        let events = erc721_events();
+        assert_eq!(events[events.len() - 2..].to_vec(), vec![
+            RawEvent::ApprovalForAll(account(1), account(2), false),
+            RawEvent::ApprovalForAll(account(1), account(3), false),
+        ]);
+    });
+}
+
+#[test]
+fn count_where_counts_matches_among_the_first_tokens() {
+    with_externalities(&mut new_test_ext(), || {
+        for n in 1..6 {
+            assert_ok!(Erc721::mint(Origin::ROOT, account(n % 2 + 1), token(n)));
+        }
+        let owned_by_one = |token_id: &H256| Erc721::owner_of(token_id) == Some(account(1));
+
+        assert_eq!(Erc721::count_where(owned_by_one, 100), 2);
+        assert_eq!(Erc721::count_where(owned_by_one, 3), 1);
+        assert_eq!(Erc721::count_where(|_| true, 0), 0);
+    });
+}
+
+#[test]
+fn a_minter_can_sponsor_a_mint_to_another_account() {
+    with_externalities(&mut new_test_ext(), || {
+        <MintPrice<Test>>::put(100);
+        assert_noop!(Erc721::sponsor_mint(Origin::signed(account(1)), account(2), token(1)), "You are not a minter");
+        assert_noop!(Erc721::set_minter(Origin::signed(account(1)), account(1), true), "bad origin: expected to be a root origin");
+
+        assert_ok!(Erc721::set_minter(Origin::ROOT, account(1), true));
+        assert_ok!(Erc721::sponsor_mint(Origin::signed(account(1)), account(2), token(1)));
+
+        assert_eq!(Erc721::owner_of(token(1)), Some(account(2)));
+        assert_eq!(Erc721::creator_of(token(1)), Some(account(1)));
+        assert_eq!(Erc721::creator_mint_count(&account(1)), 1);
+        assert_eq!(Balances::free_balance(&account(1)), ENDOWMENT - 100);
+        assert_eq!(Balances::free_balance(&account(2)), ENDOWMENT);
+
+        assert_ok!(Erc721::set_minter(Origin::ROOT, account(1), false));
+        assert_noop!(Erc721::sponsor_mint(Origin::signed(account(1)), account(2), token(2)), "You are not a minter");
+    });
+}
+
+#[test]
+fn every_mint_is_only_open_inside_the_mint_window() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_noop!(Erc721::set_mint_window(Origin::ROOT, Some((5, 4))), "Mint window ends before it starts");
+        assert_ok!(Erc721::set_mint_window(Origin::ROOT, Some((2, 3))));
+
+        System::set_block_number(1);
+        assert_noop!(Erc721::create_token(Origin::signed(account(1))), "Minting not open");
+        assert_noop!(Erc721::mint(Origin::ROOT, account(1), token(1)), "Minting not open");
+        System::set_block_number(2);
+        assert_ok!(Erc721::create_token(Origin::signed(account(1))));
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+        System::set_block_number(3);
+        assert_ok!(Erc721::create_token(Origin::signed(account(1))));
+        System::set_block_number(4);
+        assert_noop!(Erc721::create_token(Origin::signed(account(1))), "Minting not open");
+        assert_noop!(Erc721::mint(Origin::ROOT, account(1), token(2)), "Minting not open");
+
+        assert_ok!(Erc721::set_mint_window(Origin::ROOT, None));
+        assert_ok!(Erc721::create_token(Origin::signed(account(1))));
+        assert_eq!(Erc721::balance_of(&account(1)), 4);
+    });
+}
+
+#[test]
+fn a_mint_at_the_last_enumeration_index_is_rejected_cleanly() {
+    with_externalities(&mut new_test_ext(), || {
+        <TotalSupply<Test>>::put(u64::max_value());
+
+        assert_noop!(Erc721::mint(Origin::ROOT, account(1), token(1)), "Enumeration index overflow");
+        assert_noop!(Erc721::create_token(Origin::signed(account(1))), "Enumeration index overflow");
+
+        assert_eq!(Erc721::owner_of(token(1)), None);
+        assert_eq!(Erc721::balance_of(&account(1)), 0);
+        assert_eq!(Erc721::total_supply(), u64::max_value());
+        assert!(!<AllTokensIndex<Test>>::exists(token(1)));
+    });
+}
+
+#[test]
+fn self_operators_are_only_recorded_when_allowed() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_noop!(
+            Erc721::set_approval_for_all(Origin::signed(account(1)), account(1), true),
+            "You are already implicity approved for your own actions"
+        );
+
+        <AllowSelfOperator<Test>>::put(true);
+        assert_ok!(Erc721::set_approval_for_all(Origin::signed(account(1)), account(1), true));
+
+        assert!(Erc721::is_approved_for_all((account(1), account(1))));
+        assert_eq!(Erc721::approved_operators(&account(1)), vec![account(1)]);
+    });
+}
+
+#[test]
+fn all_tokens_paged_pages_through_every_token() {
+    with_externalities(&mut new_test_ext(), || {
+        for n in 1..6 {
+            assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(n)));
+        }
+
+        assert_eq!(Erc721::all_tokens_paged(0, 2), vec![token(1), token(2)]);
+        assert_eq!(Erc721::all_tokens_paged(2, 2), vec![token(3), token(4)]);
+        assert_eq!(Erc721::all_tokens_paged(4, 2), vec![token(5)]);
+        assert!(Erc721::all_tokens_paged(6, 2).is_empty());
+    });
+}
+
+#[test]
+fn batches_over_max_batch_size_are_rejected() {
+    with_externalities(&mut new_test_ext(), || {
+        <MaxBatchSize<Test>>::put(2);
+        for n in 1..4 {
+            assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(n)));
+        }
+        let too_many = vec![token(1), token(2), token(3)];
+
+        assert_noop!(Erc721::approve_many(Origin::signed(account(1)), account(2), too_many.clone()), "Batch too large");
+        assert_noop!(Erc721::create_bundle(Origin::signed(account(1)), too_many.clone()), "Batch too large");
+        assert_noop!(Erc721::burn_many(Origin::signed(account(1)), too_many), "Batch too large");
+
+        assert_ok!(Erc721::approve_many(Origin::signed(account(1)), account(2), vec![token(1), token(2)]));
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![token(1), token(2)]));
+    });
+}
+
+#[test]
+fn burn_all_of_burns_every_token_of_the_account() {
+    with_externalities(&mut new_test_ext(), || {
+        for n in 1..4 {
+            assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(n)));
+        }
+        assert_ok!(Erc721::mint(Origin::ROOT, account(2), token(4)));
+        assert_noop!(Erc721::burn_all_of(Origin::signed(account(1)), account(1)), "bad origin: expected to be a root origin");
+
+        assert_ok!(Erc721::burn_all_of(Origin::ROOT, account(1)));
+
+        assert_eq!(Erc721::balance_of(&account(1)), 0);
+        assert_eq!(Erc721::total_supply(), 1);
+        let burned: Vec<_> = erc721_events().into_iter().filter_map(|e| match e {
+            RawEvent::Burned(_, token_id) => Some(token_id),
+            _ => None,
+        }).collect();
+        assert_eq!(burned, vec![token(3), token(2), token(1)]);
+    });
+}
+
+#[test]
+fn burn_all_of_keeps_the_supply_and_both_enumerations_consistent() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+        assert_ok!(Erc721::mint(Origin::ROOT, account(2), token(2)));
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(3)));
+        assert_ok!(Erc721::mint(Origin::ROOT, account(2), token(4)));
+
+        assert_noop!(Erc721::burn_all_of(Origin::signed(account(2)), account(1)), "bad origin: expected to be a root origin");
+        assert_ok!(Erc721::burn_all_of(Origin::ROOT, account(1)));
+
+        assert_eq!(Erc721::total_supply(), 2);
+        assert_eq!(Erc721::balance_of(&account(1)), 0);
+        assert!(Erc721::owned_tokens_raw(&account(1)).is_empty());
+        assert!(!<OwnedTokens<Test>>::exists((account(1), 0)));
+        assert_eq!(Erc721::balance_of(&account(2)), 2);
+        assert_eq!(Erc721::owned_tokens_raw(&account(2)), vec![token(2), token(4)]);
+
+        let all_tokens = Erc721::all_tokens_paged(0, 10);
+        assert_eq!(all_tokens.len(), 2);
+        for (index, token_id) in all_tokens.iter().enumerate() {
+            assert_eq!(<AllTokensIndex<Test>>::get(token_id), index as u64);
+            assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+        }
+        assert!(!<AllTokens<Test>>::exists(2));
+    });
+}
+
+#[test]
+fn burn_all_of_burns_at_most_max_batch_size_tokens_per_call() {
+    with_externalities(&mut new_test_ext(), || {
+        <MaxBatchSize<Test>>::put(2);
+        for n in 1..4 {
+            assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(n)));
+        }
+
+        assert_ok!(Erc721::burn_all_of(Origin::ROOT, account(1)));
+        assert_eq!(Erc721::owned_tokens_raw(&account(1)), vec![token(1)]);
+        assert_ok!(Erc721::burn_all_of(Origin::ROOT, account(1)));
+        assert_eq!(Erc721::balance_of(&account(1)), 0);
+    });
+}
+
+#[test]
+fn on_finalise_records_the_blocks_mints_burns_and_transfers() {
+    use runtime_primitives::traits::OnFinalise;
+
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(1);
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(1)));
+        assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(2)));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token(1)));
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![token(2)]));
+        Erc721::on_finalise(1);
+
+        System::set_block_number(2);
+        Erc721::on_finalise(2);
+
+        assert_eq!(Erc721::block_stats(1), (2, 1, 1));
+        assert_eq!(Erc721::block_stats(2), (0, 0, 0));
+        assert!(!<BlockStats<Test>>::exists(2));
+        assert_eq!(<BlockMints<Test>>::get(), 0);
+    });
+}
+
+#[test]
+fn a_wrapped_token_keeps_its_external_reference_until_burned() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_noop!(Erc721::mint_wrapped(Origin::signed(account(1)), token(1), b"btc:1".to_vec()), "You are not a minter");
+        assert_ok!(Erc721::set_minter(Origin::ROOT, account(1), true));
+        assert_noop!(Erc721::mint_wrapped(Origin::signed(account(1)), token(1), vec![]), "External reference is empty");
+        assert_noop!(Erc721::mint_wrapped(Origin::signed(account(1)), token(1), vec![0; 129]), "External reference is too long");
+
+        assert_ok!(Erc721::mint_wrapped(Origin::signed(account(1)), token(1), b"btc:1".to_vec()));
+        assert_eq!(Erc721::owner_of(token(1)), Some(account(1)));
+        assert_eq!(Erc721::wrapped_asset(token(1)), Some(b"btc:1".to_vec()));
+        assert_eq!(last_event(), Some(RawEvent::Wrapped(token(1), b"btc:1".to_vec())));
+
+        assert_ok!(Erc721::burn_many(Origin::signed(account(1)), vec![token(1)]));
+        assert_eq!(Erc721::wrapped_asset(token(1)), None);
+        assert_eq!(last_event(), Some(RawEvent::Unwrapped(token(1))));
+    });
+}
+
+#[test]
+fn strict_approval_needs_an_approval_before_any_transfer() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        <StrictApproval<Test>>::put(true);
+
+        assert_noop!(
+            Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id),
+            "Token has no active approval"
+        );
+
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(3), token_id));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+
+        <StrictApproval<Test>>::put(false);
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(2)), account(2), account(1), token_id));
+    });
+}
+
+#[test]
+fn is_transferable_follows_locks_bundles_and_strict_approval() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert!(Erc721::is_transferable(token_id));
+        assert!(!Erc721::is_transferable(token(99)));
+
+        <StrictApproval<Test>>::put(true);
+        assert!(!Erc721::is_transferable(token_id));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(2), token_id));
+        assert!(Erc721::is_transferable(token_id));
+        <StrictApproval<Test>>::put(false);
+
+        assert_ok!(Erc721::create_bundle(Origin::signed(account(1)), vec![token_id]));
+        assert!(!Erc721::is_transferable(token_id));
+
+        let locked = locked_token_for(account(1), account(3), b"staking");
+        assert!(!Erc721::is_transferable(locked));
+    });
+}
+
+#[test]
+fn is_transferable_exempts_the_module_account_from_strict_approval() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), Erc721::account_id(), token_id));
+        <StrictApproval<Test>>::put(true);
+
+        assert!(Erc721::is_transferable(token_id));
+        assert_ok!(Erc721::reclaim(Origin::ROOT, token_id, account(1)));
+
+        assert!(!Erc721::is_transferable(token_id));
+    });
+}
+
+#[test]
+fn balance_changed_is_only_emitted_while_enabled() {
+    with_externalities(&mut new_test_ext(), || {
+        let token_id = create_token_for(account(1));
+        let balance_events = || erc721_events().into_iter().filter(|e| match e {
+            RawEvent::BalanceChanged(..) => true,
+            _ => false,
+        }).collect::<Vec<_>>();
+        assert!(balance_events().is_empty());
+
+        <EmitBalanceEvents<Test>>::put(true);
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), account(2), token_id));
+
+        assert_eq!(balance_events(), vec![
+            RawEvent::BalanceChanged(account(1), 1, 0),
+            RawEvent::BalanceChanged(account(2), 0, 1),
+        ]);
+    });
+}
+
+#[test]
+fn can_mint_reports_the_error_the_mint_would_fail_with() {
+    with_externalities(&mut new_test_ext(), || {
+        let minter = account(1);
+        let assert_same_error = |token_id: H256, e: &'static str| {
+            assert_eq!(Erc721::can_mint(minter, account(2), token_id), Err(e));
+            assert_noop!(Erc721::sponsor_mint(Origin::signed(minter), account(2), token_id), e);
+        };
+
+        assert_same_error(token(1), "You are not a minter");
+        assert_ok!(Erc721::set_minter(Origin::ROOT, minter, true));
+        assert_ok!(Erc721::can_mint(minter, account(2), token(1)));
+        assert_ok!(Erc721::sponsor_mint(Origin::signed(minter), account(2), token(1)));
+
+        assert_same_error(token(1), "Token already exists");
+
+        assert_ok!(Erc721::reserve_token_id(Origin::ROOT, token(2)));
+        assert_same_error(token(2), "Token id is reserved");
+
+        assert_ok!(Erc721::set_mint_window(Origin::ROOT, Some((5, 6))));
+        assert_same_error(token(3), "Minting not open");
+        assert_ok!(Erc721::set_mint_window(Origin::ROOT, None));
+
+        assert_ok!(Erc721::set_per_creator_cap(Origin::ROOT, Some(1)));
+        assert_same_error(token(3), "Creator has reached the mint cap");
+        assert_ok!(Erc721::set_per_creator_cap(Origin::ROOT, None));
+
+        <MintPrice<Test>>::put(ENDOWMENT + 1);
+        assert_same_error(token(3), "Not enough free balance to pay the mint price");
+
+        assert_eq!(Erc721::can_mint(minter, minter, token(3)), Err("Not enough free balance to pay the mint price"));
+        assert_noop!(Erc721::create_token(Origin::signed(minter)), "Not enough free balance to pay the mint price");
+    });
+}
+
+#[test]
+fn reclaim_clears_the_approval_even_when_transfers_keep_it() {
+    with_externalities(&mut new_test_ext(), || {
+        <ClearApprovalOnTransfer<Test>>::put(false);
+        let token_id = create_token_for(account(1));
+        assert_ok!(Erc721::approve(Origin::signed(account(1)), account(3), token_id));
+        assert_ok!(Erc721::transfer_from(Origin::signed(account(1)), account(1), Erc721::account_id(), token_id));
+        assert_eq!(Erc721::get_approved(token_id), Some(account(3)));
+
+        assert_ok!(Erc721::reclaim(Origin::ROOT, token_id, account(2)));
+
+        assert_eq!(Erc721::owner_of(token_id), Some(account(2)));
+        assert_eq!(Erc721::get_approved(token_id), None);
+        assert_eq!(last_event(), Some(RawEvent::Reclaimed(token_id, account(2))));
+    });
+}
+
+#[test]
+fn tokens_in_range_returns_the_tokens_at_those_indexes() {
+    with_externalities(&mut new_test_ext(), || {
+        for n in 1..5 {
+            assert_ok!(Erc721::mint(Origin::ROOT, account(1), token(n)));
+        }
+
+        assert_eq!(Erc721::tokens_in_range(1, 3), vec![token(2), token(3)]);
+        assert_eq!(Erc721::tokens_in_range(2, 10), vec![token(3), token(4)]);
+        assert!(Erc721::tokens_in_range(2, 2).is_empty());
+        assert!(Erc721::tokens_in_range(3, 1).is_empty());
+    });
+}
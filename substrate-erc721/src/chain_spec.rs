@@ -1,6 +1,7 @@
 use primitives::{Ed25519AuthorityId, ed25519};
 use substrate_erc721_runtime::{
 	AccountId, GenesisConfig, ConsensusConfig, TimestampConfig, BalancesConfig, SudoConfig,
+	Erc721Config,
 };
 use substrate_service;
 
@@ -97,5 +98,17 @@ fn testnet_genesis(initial_authorities: Vec<Ed25519AuthorityId>, endowed_account
 		sudo: Some(SudoConfig {
 			key: root_key,
 		}),
+		erc721: Some(Erc721Config {
+			max_operators_per_owner: 16,
+			max_batch_size: 50,
+			clear_approval_on_transfer: true,
+			marketplace_fee_bps: 0,
+			refund_amount: 0,
+			stable_ordering: false,
+			allow_self_operator: false,
+			reject_self_transfer: false,
+			strict_approval: false,
+			emit_balance_events: false,
+		}),
 	}
 }